@@ -5,6 +5,8 @@ import { defineConfig } from "eslint/config";
 
 export default defineConfig({
   files: ["**/*.ts", "**/*.tsx"],
+  // Generated by tauri-specta in debug builds
+  ignores: ["src/bindings.ts"],
   // Extend recommended rule sets from:
   // 1. ESLint JS's recommended rules
   // 2. TypeScript ESLint recommended rules
@@ -1,5 +1,9 @@
-use crate::app_state::registry::AppState;
+use std::path::PathBuf;
 
-pub async fn initialize_app_state() -> Result<AppState, String> {
-    Ok(AppState::new())
+use crate::{app_state::registry::AppState, tags::tag_manager::TagManager};
+
+pub async fn initialize_app_state(data_dir: PathBuf) -> Result<AppState, String> {
+    let tag_manager = TagManager::load(data_dir.join("tags.json")).map_err(|e| e.to_string())?;
+
+    Ok(AppState::new(tag_manager))
 }
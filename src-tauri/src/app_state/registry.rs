@@ -1,19 +1,34 @@
 use std::sync::Arc;
 
-use crate::modbus::connection_manager::ModbusConnectionManager;
+use crate::{
+    modbus::{connection_manager::ModbusConnectionManager, server_manager::ModbusServerManager},
+    tags::tag_manager::TagManager,
+};
 
 pub struct AppState {
     connection_manager: Arc<ModbusConnectionManager>,
+    server_manager: Arc<ModbusServerManager>,
+    tag_manager: Arc<TagManager>,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub fn new(tag_manager: TagManager) -> Self {
         Self {
             connection_manager: Arc::new(ModbusConnectionManager::new()),
+            server_manager: Arc::new(ModbusServerManager::new()),
+            tag_manager: Arc::new(tag_manager),
         }
     }
 
     pub fn connection_manager(&self) -> Arc<ModbusConnectionManager> {
         Arc::clone(&self.connection_manager)
     }
+
+    pub fn server_manager(&self) -> Arc<ModbusServerManager> {
+        Arc::clone(&self.server_manager)
+    }
+
+    pub fn tag_manager(&self) -> Arc<TagManager> {
+        Arc::clone(&self.tag_manager)
+    }
 }
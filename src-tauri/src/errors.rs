@@ -1,11 +1,18 @@
+use serde::Serialize;
+use specta::Type;
 use thiserror::Error;
 
+use crate::modbus::pdu;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Modbus error: {0}")]
     ModbusError(#[from] ModbusError),
+
+    #[error("Tag error: {0}")]
+    TagError(#[from] TagError),
 }
 
 #[derive(Error, Debug)]
@@ -27,6 +34,144 @@ pub enum ModbusError {
 
     #[error("TCP host error: {0}")]
     TCPHostError(#[from] std::net::AddrParseError),
+
+    #[error("Modbus exception: {0}")]
+    Exception(rodbus::ExceptionCode),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("TLS configuration error: {0}")]
+    TlsConfig(String),
+
+    #[error("TLS handshake failed: {0}")]
+    TlsHandshake(String),
+
+    #[error("Connection {0} not found")]
+    ConnectionNotFound(u32),
+
+    #[error("Server {0} not found")]
+    ServerNotFound(u32),
+
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    #[error("Request for {count} items at address {start} failed: {source}")]
+    ChunkError {
+        start: u16,
+        count: u16,
+        source: Box<Error>,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum TagError {
+    #[error("Tag not found: {0}")]
+    NotFound(String),
+
+    #[error("Tag is read-only: {0}")]
+    ReadOnly(String),
+
+    #[error("Tag file error: {0}")]
+    Io(std::io::Error),
+
+    #[error("Tag file format error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+impl Error {
+    pub fn invalid_parameter(message: impl Into<String>) -> Self {
+        Error::ModbusError(ModbusError::InvalidParameter(message.into()))
+    }
+
+    pub fn connection_not_found(id: u32) -> Self {
+        Error::ModbusError(ModbusError::ConnectionNotFound(id))
+    }
+
+    pub fn server_not_found(id: u32) -> Self {
+        Error::ModbusError(ModbusError::ServerNotFound(id))
+    }
+
+    // Tags the error with the connection it occurred on
+    pub fn on_connection(self, connection_id: u32) -> CommandError {
+        CommandError {
+            connection_id: Some(connection_id),
+            ..self.into()
+        }
+    }
+
+    // Stable identifier the frontend can match on
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::TagError(TagError::NotFound(_)) => "tagNotFound",
+            Error::TagError(TagError::ReadOnly(_)) => "tagReadOnly",
+            Error::TagError(TagError::Io(_)) => "io",
+            Error::TagError(TagError::Serialization(_)) => "serialization",
+            Error::ModbusError(err) => match err {
+                ModbusError::ConnectionError(_) => "channelClosed",
+                ModbusError::RequestError(err) => match err {
+                    rodbus::RequestError::Exception(_) => "exception",
+                    rodbus::RequestError::ResponseTimeout => "timeout",
+                    rodbus::RequestError::NoConnection => "noConnection",
+                    rodbus::RequestError::Shutdown => "channelClosed",
+                    rodbus::RequestError::Io(_) => "io",
+                    rodbus::RequestError::BadRequest(_) => "invalidRequest",
+                    _ => "requestFailed",
+                },
+                ModbusError::InvalidRequest(_) => "invalidRequest",
+                ModbusError::Error(_) => "other",
+                ModbusError::MismatchedConnectionSettings => "mismatchedSettings",
+                ModbusError::TCPHostError(_) => "invalidHost",
+                ModbusError::Exception(_) => "exception",
+                ModbusError::IoError(_) => "io",
+                ModbusError::InvalidResponse(_) => "invalidResponse",
+                ModbusError::TlsConfig(_) => "tlsConfig",
+                ModbusError::TlsHandshake(_) => "tlsHandshake",
+                ModbusError::ConnectionNotFound(_) => "connectionNotFound",
+                ModbusError::ServerNotFound(_) => "serverNotFound",
+                ModbusError::InvalidParameter(_) => "invalidParameter",
+                ModbusError::ChunkError { source, .. } => source.kind(),
+            },
+        }
+    }
+
+    pub fn exception_code(&self) -> Option<u8> {
+        match self {
+            Error::ModbusError(ModbusError::Exception(code))
+            | Error::ModbusError(ModbusError::RequestError(rodbus::RequestError::Exception(
+                code,
+            ))) => Some(pdu::exception_code(*code)),
+            Error::ModbusError(ModbusError::ChunkError { source, .. }) => source.exception_code(),
+            _ => None,
+        }
+    }
+}
+
+// Error as returned to the frontend by commands
+#[derive(Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandError {
+    pub kind: String,
+    pub message: String,
+    pub exception_code: Option<u8>,
+    pub connection_id: Option<u32>,
+}
+
+impl From<Error> for CommandError {
+    fn from(err: Error) -> Self {
+        CommandError {
+            kind: err.kind().to_string(),
+            message: err.to_string(),
+            exception_code: err.exception_code(),
+            connection_id: match err {
+                Error::ModbusError(ModbusError::ConnectionNotFound(id)) => Some(id),
+                _ => None,
+            },
+        }
+    }
 }
 
 impl From<rodbus::Shutdown> for Error {
@@ -58,3 +203,9 @@ impl From<String> for Error {
         Error::ModbusError(ModbusError::Error(err))
     }
 }
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::ModbusError(ModbusError::IoError(err))
+    }
+}
@@ -2,19 +2,132 @@ mod app_state;
 mod errors;
 mod modbus;
 mod system;
+mod tags;
 
 use app_state::initialize_app_state;
 use log::{error, info};
 use modbus::{
-    close_connection, connect_modbus_rtu, connect_modbus_tcp, read_coils, read_discrete_inputs,
-    read_holding_registers, read_input_registers, write_multiple_coils, write_multiple_registers,
-    write_single_coil, write_single_register,
+    clear_diagnostic_counters, clear_traffic, close_connection, connect_modbus_ascii,
+    connect_modbus_rtu, connect_modbus_rtu_over_tcp, connect_modbus_tcp, connect_modbus_tls,
+    connect_modbus_udp, diagnostics_loopback, export_traffic, get_comm_event_counter,
+    get_comm_event_log, get_connection_status, get_traffic, mask_write_register, poller::PollEvent,
+    read_coils, read_decoded_registers, read_device_identification, read_diagnostic_counters,
+    read_discrete_inputs, read_exception_status, read_fifo_queue, read_file_record,
+    read_holding_registers, read_input_registers, read_server_registers,
+    read_write_multiple_registers, report_server_id, send_raw_pdu, set_decode_level,
+    sniffer::SniffedFrame, start_modbus_gateway, start_modbus_rtu_server, start_modbus_sniffer,
+    start_modbus_tcp_server, start_poll_job, status::ConnectionStatus, stop_modbus_gateway,
+    stop_modbus_server, stop_modbus_sniffer, stop_poll_job, traffic::TrafficRecord,
+    write_encoded_registers, write_file_record, write_multiple_coils, write_multiple_registers,
+    write_server_registers, write_single_coil, write_single_register,
 };
+#[cfg(any(debug_assertions, test))]
+use specta_typescript::{BigIntExportBehavior, Typescript};
 use system::get_ports;
+use tags::{delete_tag, list_tags, read_tag, read_tags, save_tag, write_tag};
 use tauri::{AppHandle, LogicalPosition, Manager, Position, TitleBarStyle};
+use tauri_specta::{collect_commands, Builder};
+
+// Frontend bindings, kept in sync by the bindings_are_up_to_date test
+#[cfg(any(debug_assertions, test))]
+const BINDINGS_PATH: &str = "../src/bindings.ts";
+
+// 64-bit fields are exported as numbers: timestamps and counters stay far
+// below 2^53, and TypedValue sends larger integers as strings
+#[cfg(any(debug_assertions, test))]
+fn typescript() -> Typescript {
+    Typescript::default()
+        .header("// @ts-nocheck")
+        .bigint(BigIntExportBehavior::Number)
+}
+
+fn specta_builder() -> Builder<tauri::Wry> {
+    Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            // Modbus Connection
+            connect_modbus_tcp,
+            connect_modbus_rtu,
+            connect_modbus_rtu_over_tcp,
+            connect_modbus_ascii,
+            connect_modbus_udp,
+            connect_modbus_tls,
+            close_connection,
+            get_connection_status,
+            // Modbus Sniffer
+            start_modbus_sniffer,
+            stop_modbus_sniffer,
+            // Read Modbus
+            read_coils,
+            read_device_identification,
+            report_server_id,
+            read_discrete_inputs,
+            read_holding_registers,
+            read_input_registers,
+            // Write Modbus
+            write_multiple_coils,
+            write_multiple_registers,
+            write_single_coil,
+            write_single_register,
+            read_write_multiple_registers,
+            mask_write_register,
+            // Serial diagnostics
+            diagnostics_loopback,
+            read_diagnostic_counters,
+            clear_diagnostic_counters,
+            read_exception_status,
+            get_comm_event_counter,
+            get_comm_event_log,
+            // FIFO queue
+            read_fifo_queue,
+            // File records
+            read_file_record,
+            write_file_record,
+            // Raw PDU
+            send_raw_pdu,
+            // Traffic
+            get_traffic,
+            clear_traffic,
+            export_traffic,
+            set_decode_level,
+            // Typed Data
+            read_decoded_registers,
+            write_encoded_registers,
+            // Tags
+            list_tags,
+            save_tag,
+            delete_tag,
+            read_tag,
+            read_tags,
+            write_tag,
+            // Polling
+            start_poll_job,
+            stop_poll_job,
+            // Modbus Server
+            start_modbus_tcp_server,
+            start_modbus_rtu_server,
+            stop_modbus_server,
+            read_server_registers,
+            write_server_registers,
+            // Modbus Gateway
+            start_modbus_gateway,
+            stop_modbus_gateway,
+            // Windows
+            create_window,
+            // Utils
+            get_ports
+        ])
+        // Event payloads
+        .typ::<PollEvent>()
+        .typ::<TrafficRecord>()
+        .typ::<SniffedFrame>()
+        .typ::<ConnectionStatus>()
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let builder = specta_builder();
+    let invoke_handler = builder.invoke_handler();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(
@@ -23,10 +136,18 @@ pub fn run() {
                 .build(),
         )
         .setup(move |app| {
+            // Refreshes the bindings while developing; a read-only tree only
+            // loses the refresh
+            #[cfg(debug_assertions)]
+            if let Err(err) = builder.export(typescript(), BINDINGS_PATH) {
+                error!("Failed to export TypeScript bindings: {}", err);
+            }
+
             let handle = app.handle().clone();
+            let data_dir = handle.path().app_data_dir()?;
             tauri::async_runtime::block_on(async {
                 info!("Initializing app state...");
-                let app_state = match initialize_app_state().await {
+                let app_state = match initialize_app_state(data_dir).await {
                     Ok(app_state) => app_state,
                     Err(err) => {
                         return Err(err);
@@ -42,31 +163,13 @@ pub fn run() {
             })?;
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            // Modbus Connection
-            connect_modbus_tcp,
-            connect_modbus_rtu,
-            close_connection,
-            // Read Modbus
-            read_coils,
-            read_discrete_inputs,
-            read_holding_registers,
-            read_input_registers,
-            // Write Modbus
-            write_multiple_coils,
-            write_multiple_registers,
-            write_single_coil,
-            write_single_register,
-            // Windows
-            create_window,
-            // Utils
-            get_ports
-        ])
+        .invoke_handler(invoke_handler)
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
 #[tauri::command]
+#[specta::specta]
 fn create_window(
     app: AppHandle,
     url: String,
@@ -97,3 +200,27 @@ fn create_window(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Run with UPDATE_BINDINGS=1 to rewrite src/bindings.ts after changing
+    // a command or one of its types
+    #[test]
+    fn bindings_are_up_to_date() {
+        let committed = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_PATH);
+        if std::env::var_os("UPDATE_BINDINGS").is_some() {
+            specta_builder().export(typescript(), &committed).unwrap();
+            return;
+        }
+
+        let generated = std::env::temp_dir().join("modbusx-bindings.ts");
+        specta_builder().export(typescript(), &generated).unwrap();
+        assert!(
+            std::fs::read_to_string(&generated).unwrap()
+                == std::fs::read_to_string(&committed).unwrap(),
+            "src/bindings.ts is out of date, run UPDATE_BINDINGS=1 cargo test bindings_are_up_to_date"
+        );
+    }
+}
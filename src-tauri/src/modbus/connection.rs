@@ -1,7 +1,24 @@
 use crate::errors::{Error, ModbusError, Result};
+use crate::modbus::{
+    diagnostics::SerialDiagnostics,
+    file_record::{self, FileRecord, FileRecordRequest},
+    identification::{self, DeviceIdCategory, DeviceIdentification, ServerIdReport},
+    pdu,
+    status::ConnectionStatusTracker,
+    traffic::TrafficLog,
+    transport::{
+        shared, AsciiTransport, ChannelPortTransport, PduTransport, RtuOverTcpTransport,
+        SerialSettings, SharedTransport, TcpTransport, UdpTransport,
+    },
+};
 use rodbus::client::*;
 use rodbus::*;
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::{net::SocketAddr, path::Path, sync::Arc, time::Duration};
+use tokio::sync::{watch, MutexGuard};
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1000);
 
 pub enum ModbusConnectionSettings {
     RTU {
@@ -10,15 +27,138 @@ pub enum ModbusConnectionSettings {
         data_bits: DataBits,
         parity: Parity,
         stop_bits: StopBits,
+        timeout: Duration,
+        decode_level: DecodeLevel,
     },
     TCP {
         host: String,
         port: u16,
+        timeout: Duration,
+        decode_level: DecodeLevel,
+    },
+    ASCII {
+        path: String,
+        baud_rate: u32,
+        data_bits: DataBits,
+        parity: Parity,
+        stop_bits: StopBits,
+        timeout: Duration,
+    },
+    TLS {
+        host: String,
+        port: u16,
+        timeout: Duration,
+        decode_level: DecodeLevel,
+        // Expected subject name of the server certificate
+        server_name: String,
+        ca_path: String,
+        cert_path: String,
+        key_path: String,
+        key_password: Option<String>,
+    },
+    RTUOverTCP {
+        host: String,
+        port: u16,
+        timeout: Duration,
+        frame_gap: Duration,
     },
+    UDP {
+        host: String,
+        port: u16,
+        // Per attempt
+        timeout: Duration,
+        retransmissions: u32,
+    },
+}
+
+impl ModbusConnectionSettings {
+    // Stable name of the device behind the connection, unlike the
+    // connection ID which is reused by whichever tab opens next
+    pub fn identity(&self) -> String {
+        match self {
+            ModbusConnectionSettings::RTU { path, .. } => format!("rtu:{}", path),
+            ModbusConnectionSettings::ASCII { path, .. } => format!("ascii:{}", path),
+            ModbusConnectionSettings::TCP { host, port, .. } => format!("tcp://{}:{}", host, port),
+            ModbusConnectionSettings::TLS { host, port, .. } => format!("tls://{}:{}", host, port),
+            ModbusConnectionSettings::RTUOverTCP { host, port, .. } => {
+                format!("rtu-over-tcp://{}:{}", host, port)
+            }
+            ModbusConnectionSettings::UDP { host, port, .. } => format!("udp://{}:{}", host, port),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+pub enum RegisterType {
+    #[serde(rename = "coil")]
+    Coil,
+    #[serde(rename = "discrete")]
+    DiscreteInput,
+    #[serde(rename = "holding")]
+    HoldingRegister,
+    #[serde(rename = "input")]
+    InputRegister,
 }
 
+impl TryFrom<&str> for RegisterType {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "coil" => Ok(RegisterType::Coil),
+            "discrete" => Ok(RegisterType::DiscreteInput),
+            "holding" => Ok(RegisterType::HoldingRegister),
+            "input" => Ok(RegisterType::InputRegister),
+            _ => Err(Error::invalid_parameter(format!(
+                "Invalid register type: {}",
+                value
+            ))),
+        }
+    }
+}
+
+// Protocol limits for a single request PDU
+pub const MAX_READ_BITS: u16 = 2000;
+pub const MAX_READ_REGISTERS: u16 = 125;
+pub const MAX_WRITE_COILS: u16 = 1968;
+pub const MAX_WRITE_REGISTERS: u16 = 123;
+pub const MAX_READ_WRITE_READ_REGISTERS: u16 = 125;
+pub const MAX_READ_WRITE_WRITE_REGISTERS: u16 = 121;
+
+// Splits a range into chunks of at most `max` items. Empty or overflowing
+// ranges are kept as is so the request itself reports them as invalid.
+fn split_range(start: u16, count: u16, max: u16) -> Vec<AddressRange> {
+    let end = start as u32 + count as u32;
+    if count == 0 || end > u16::MAX as u32 + 1 {
+        return vec![AddressRange { start, count }];
+    }
+
+    (start as u32..end)
+        .step_by(max as usize)
+        .map(|chunk_start| AddressRange {
+            start: chunk_start as u16,
+            count: (end - chunk_start).min(max as u32) as u16,
+        })
+        .collect()
+}
+
+// Only split requests name the failing chunk, single requests keep their error
+fn chunk_error(range: AddressRange, split: bool, err: Error) -> Error {
+    if split {
+        Error::ModbusError(ModbusError::ChunkError {
+            start: range.start,
+            count: range.count,
+            source: Box::new(err),
+        })
+    } else {
+        err
+    }
+}
+
+// Connections that are not built on a rodbus channel send every request
+// as a raw PDU instead
 pub trait HasChannel {
-    fn channel(&self) -> &Channel;
+    fn channel(&self) -> Option<&Channel>;
 }
 
 #[async_trait::async_trait]
@@ -28,22 +168,228 @@ pub trait ModbusConnectionTrait: Send + Sync + HasChannel {
         connection_settings: ModbusConnectionSettings,
     ) -> Result<()>;
 
+    // Default response timeout, overridable per request
+    fn timeout(&self) -> Duration;
+
+    fn traffic(&self) -> &TrafficLog;
+
+    // Diagnostic functions of the serial line, if this connection is on one
+    fn serial_diagnostics(&self) -> Option<&dyn SerialDiagnostics> {
+        None
+    }
+
+    // Transport for raw PDUs, used for every request of connections without a
+    // rodbus channel and for the function codes the channel does not implement
+    fn transport(&self) -> Option<&SharedTransport>;
+
+    // Connections without a rodbus channel report their state from the
+    // outcome of each request; channels report theirs through a listener
+    fn status(&self) -> Option<&ConnectionStatusTracker> {
+        None
+    }
+
+    // Exclusive use of the transport, e.g. for requests that must not be
+    // interleaved with others
+    async fn lock_transport(&self) -> Result<MutexGuard<'_, Box<dyn PduTransport>>> {
+        let transport = self.transport().ok_or_else(|| {
+            Error::ModbusError(ModbusError::Error(
+                "Only standard reads and writes are supported over TLS".to_string(),
+            ))
+        })?;
+        Ok(transport.lock().await)
+    }
+
+    // Sends a request on a transport that is already locked, with traffic capture
+    async fn exchange_pdu(
+        &self,
+        transport: &mut dyn PduTransport,
+        slave_id: u8,
+        request: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>> {
+        let timeout = timeout.unwrap_or_else(|| self.timeout());
+
+        let exchange = self.traffic().request(slave_id, request.clone());
+        let result = transport.transact(slave_id, &request, timeout).await;
+        self.traffic()
+            .pdu_response(exchange, &result, transport.last_crc_valid());
+        if let Some(status) = self.status() {
+            status.request_result(&result);
+        }
+
+        result
+    }
+
+    // Raw request with traffic capture; exception responses are returned as is
+    async fn forward_pdu(
+        &self,
+        slave_id: u8,
+        request: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>> {
+        let mut transport = self.lock_transport().await?;
+        self.exchange_pdu(&mut **transport, slave_id, request, timeout)
+            .await
+    }
+
+    // Request to every slave on a serial line; no response is awaited
+    async fn broadcast_pdu(&self, request: Vec<u8>) -> Result<()> {
+        let mut transport = self.lock_transport().await?;
+        self.traffic().request(0, request.clone());
+        transport.broadcast(&request, self.timeout()).await
+    }
+
+    // Raw request with traffic capture and exception handling
+    async fn request_pdu(
+        &self,
+        slave_id: u8,
+        request: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>> {
+        let function_code = request[0];
+        let response = self.forward_pdu(slave_id, request, timeout).await?;
+
+        pdu::check_response(function_code, response)
+    }
+
+    // Arbitrary or vendor specific function code; exception responses are
+    // returned rather than raised
+    async fn send_raw_pdu(
+        &self,
+        slave_id: u8,
+        function_code: u8,
+        payload: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<pdu::RawResponse> {
+        if function_code == 0 || function_code & pdu::EXCEPTION_FLAG != 0 {
+            return Err(Error::invalid_parameter(format!(
+                "Invalid function code: {}",
+                function_code
+            )));
+        }
+        if payload.len() >= pdu::MAX_PDU_LENGTH {
+            return Err(Error::invalid_parameter(format!(
+                "Payload of {} bytes exceeds the {} byte PDU limit",
+                payload.len(),
+                pdu::MAX_PDU_LENGTH - 1
+            )));
+        }
+        let mut request = vec![function_code];
+        request.extend(payload);
+        let response = self.forward_pdu(slave_id, request, timeout).await?;
+
+        pdu::raw_response(function_code, response)
+    }
+
+    async fn set_decode_level(&self, decode_level: DecodeLevel) -> Result<()> {
+        if let Some(channel) = self.channel() {
+            channel.clone().set_decode_level(decode_level).await?;
+        }
+        // Raw PDUs are only logged at the application layer
+        self.traffic().set_decode_level(decode_level.app);
+        Ok(())
+    }
+
+    fn request_param(&self, slave_id: u8, timeout: Option<Duration>) -> RequestParam {
+        RequestParam::new(
+            UnitId::new(slave_id),
+            timeout.unwrap_or_else(|| self.timeout()),
+        )
+    }
+
+    // One bit read request (FC 1 or 2), through the rodbus channel if there is one
+    async fn request_bits(
+        &self,
+        slave_id: u8,
+        function_code: u8,
+        range: AddressRange,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<Indexed<bool>>> {
+        pdu::check_request_range(range, MAX_READ_BITS)?;
+        let request = pdu::read_request(function_code, range);
+        let Some(channel) = self.channel() else {
+            let response = self.request_pdu(slave_id, request, timeout).await?;
+            return pdu::bits_from_response(range, &response);
+        };
+
+        let mut channel = channel.clone();
+        let request_param = self.request_param(slave_id, timeout);
+        let exchange = self.traffic().channel_request(slave_id, request);
+        let response = if function_code == pdu::READ_COILS {
+            channel.read_coils(request_param, range).await
+        } else {
+            channel.read_discrete_inputs(request_param, range).await
+        };
+        self.traffic().response(exchange, &response, |values| {
+            pdu::bits_response(function_code, values)
+        });
+
+        Ok(response?)
+    }
+
+    // One register read request (FC 3 or 4), through the rodbus channel if there is one
+    async fn request_registers(
+        &self,
+        slave_id: u8,
+        function_code: u8,
+        range: AddressRange,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<Indexed<u16>>> {
+        pdu::check_request_range(range, MAX_READ_REGISTERS)?;
+        let request = pdu::read_request(function_code, range);
+        let Some(channel) = self.channel() else {
+            let response = self.request_pdu(slave_id, request, timeout).await?;
+            return pdu::registers_from_response(range, &response);
+        };
+
+        let mut channel = channel.clone();
+        let request_param = self.request_param(slave_id, timeout);
+        let exchange = self.traffic().channel_request(slave_id, request);
+        let response = if function_code == pdu::READ_HOLDING_REGISTERS {
+            channel.read_holding_registers(request_param, range).await
+        } else {
+            channel.read_input_registers(request_param, range).await
+        };
+        self.traffic().response(exchange, &response, |values| {
+            pdu::registers_response(function_code, values)
+        });
+
+        Ok(response?)
+    }
+
+    // Request whose response must repeat the first `echoed` bytes of the request
+    async fn request_echo(
+        &self,
+        slave_id: u8,
+        request: Vec<u8>,
+        echoed: usize,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let response = self.request_pdu(slave_id, request.clone(), timeout).await?;
+        if response.get(..echoed) != request.get(..echoed) {
+            return Err(pdu::invalid_response("Write was not echoed"));
+        }
+        Ok(())
+    }
+
     // Read Methods
     async fn read_coils(
         &self,
         slave_id: u8,
         start_address: u16,
         count: u16,
+        timeout: Option<Duration>,
     ) -> Result<Vec<Indexed<bool>>> {
-        let mut channel = self.channel().clone();
+        let mut result = Vec::with_capacity(count as usize);
 
-        let request_param = RequestParam::new(UnitId::new(slave_id), Duration::from_millis(1000));
-        let range = AddressRange {
-            start: start_address,
-            count,
-        };
+        for range in split_range(start_address, count, MAX_READ_BITS) {
+            let values = self
+                .request_bits(slave_id, pdu::READ_COILS, range, timeout)
+                .await
+                .map_err(|e| chunk_error(range, count > MAX_READ_BITS, e))?;
+            result.extend(values);
+        }
 
-        let result = channel.read_coils(request_param, range).await?;
         Ok(result)
     }
 
@@ -52,15 +398,18 @@ pub trait ModbusConnectionTrait: Send + Sync + HasChannel {
         slave_id: u8,
         start_address: u16,
         count: u16,
+        timeout: Option<Duration>,
     ) -> Result<Vec<Indexed<u16>>> {
-        let mut channel = self.channel().clone();
-        let request_param = RequestParam::new(UnitId::new(slave_id), Duration::from_millis(1000));
-        let range = AddressRange {
-            start: start_address,
-            count,
-        };
+        let mut result = Vec::with_capacity(count as usize);
+
+        for range in split_range(start_address, count, MAX_READ_REGISTERS) {
+            let values = self
+                .request_registers(slave_id, pdu::READ_HOLDING_REGISTERS, range, timeout)
+                .await
+                .map_err(|e| chunk_error(range, count > MAX_READ_REGISTERS, e))?;
+            result.extend(values);
+        }
 
-        let result = channel.read_holding_registers(request_param, range).await?;
         Ok(result)
     }
 
@@ -69,15 +418,18 @@ pub trait ModbusConnectionTrait: Send + Sync + HasChannel {
         slave_id: u8,
         start_address: u16,
         count: u16,
+        timeout: Option<Duration>,
     ) -> Result<Vec<Indexed<u16>>> {
-        let mut channel = self.channel().clone();
-        let request_param = RequestParam::new(UnitId::new(slave_id), Duration::from_millis(1000));
-        let range = AddressRange {
-            start: start_address,
-            count,
-        };
+        let mut result = Vec::with_capacity(count as usize);
+
+        for range in split_range(start_address, count, MAX_READ_REGISTERS) {
+            let values = self
+                .request_registers(slave_id, pdu::READ_INPUT_REGISTERS, range, timeout)
+                .await
+                .map_err(|e| chunk_error(range, count > MAX_READ_REGISTERS, e))?;
+            result.extend(values);
+        }
 
-        let result = channel.read_input_registers(request_param, range).await?;
         Ok(result)
     }
 
@@ -86,83 +438,375 @@ pub trait ModbusConnectionTrait: Send + Sync + HasChannel {
         slave_id: u8,
         start_address: u16,
         count: u16,
+        timeout: Option<Duration>,
     ) -> Result<Vec<Indexed<bool>>> {
-        let mut channel = self.channel().clone();
-        let request_param = RequestParam::new(UnitId::new(slave_id), Duration::from_millis(1000));
+        let mut result = Vec::with_capacity(count as usize);
+
+        for range in split_range(start_address, count, MAX_READ_BITS) {
+            let values = self
+                .request_bits(slave_id, pdu::READ_DISCRETE_INPUTS, range, timeout)
+                .await
+                .map_err(|e| chunk_error(range, count > MAX_READ_BITS, e))?;
+            result.extend(values);
+        }
+
+        Ok(result)
+    }
+
+    async fn read_table(
+        &self,
+        slave_id: u8,
+        register_type: RegisterType,
+        start_address: u16,
+        count: u16,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<Indexed<u16>>> {
+        let values = match register_type {
+            RegisterType::Coil => self
+                .read_coils(slave_id, start_address, count, timeout)
+                .await?
+                .into_iter()
+                .map(|indexed| Indexed::new(indexed.index, indexed.value as u16))
+                .collect(),
+            RegisterType::DiscreteInput => self
+                .read_discrete_inputs(slave_id, start_address, count, timeout)
+                .await?
+                .into_iter()
+                .map(|indexed| Indexed::new(indexed.index, indexed.value as u16))
+                .collect(),
+            RegisterType::HoldingRegister => {
+                self.read_holding_registers(slave_id, start_address, count, timeout)
+                    .await?
+            }
+            RegisterType::InputRegister => {
+                self.read_input_registers(slave_id, start_address, count, timeout)
+                    .await?
+            }
+        };
+
+        Ok(values)
+    }
+
+    // One Write Multiple Coils request (FC 15), through the rodbus channel if there is one
+    async fn request_write_coils(
+        &self,
+        slave_id: u8,
+        start_address: u16,
+        values: Vec<bool>,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
         let range = AddressRange {
             start: start_address,
-            count,
+            count: values.len() as u16,
+        };
+        pdu::check_request_range(range, MAX_WRITE_COILS)?;
+        let request = pdu::write_multiple_coils(start_address, &values);
+        let Some(channel) = self.channel() else {
+            return self.request_echo(slave_id, request, 5, timeout).await;
         };
 
-        let result = channel.read_discrete_inputs(request_param, range).await?;
-        Ok(result)
+        let mut channel = channel.clone();
+        let request_param = self.request_param(slave_id, timeout);
+        let values = WriteMultiple::from(start_address, values)?;
+        let exchange = self.traffic().channel_request(slave_id, request);
+        let response = channel.write_multiple_coils(request_param, values).await;
+        self.traffic().response(exchange, &response, |written| {
+            pdu::read_request(pdu::WRITE_MULTIPLE_COILS, *written)
+        });
+
+        response?;
+        Ok(())
+    }
+
+    // One Write Multiple Registers request (FC 16), through the rodbus channel if there is one
+    async fn request_write_registers(
+        &self,
+        slave_id: u8,
+        start_address: u16,
+        values: Vec<u16>,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let range = AddressRange {
+            start: start_address,
+            count: values.len() as u16,
+        };
+        pdu::check_request_range(range, MAX_WRITE_REGISTERS)?;
+        let request = pdu::write_multiple_registers(start_address, &values);
+        let Some(channel) = self.channel() else {
+            return self.request_echo(slave_id, request, 5, timeout).await;
+        };
+
+        let mut channel = channel.clone();
+        let request_param = self.request_param(slave_id, timeout);
+        let values = WriteMultiple::from(start_address, values)?;
+        let exchange = self.traffic().channel_request(slave_id, request);
+        let response = channel
+            .write_multiple_registers(request_param, values)
+            .await;
+        self.traffic().response(exchange, &response, |written| {
+            pdu::read_request(pdu::WRITE_MULTIPLE_REGISTERS, *written)
+        });
+
+        response?;
+        Ok(())
     }
 
     // Write Methods
-    async fn write_single_coil(&self, slave_id: u8, value: Indexed<bool>) -> Result<Indexed<bool>> {
-        let mut channel = self.channel().clone();
-        let request_param = RequestParam::new(UnitId::new(slave_id), Duration::from_millis(1000));
+    async fn write_single_coil(
+        &self,
+        slave_id: u8,
+        value: Indexed<bool>,
+        timeout: Option<Duration>,
+    ) -> Result<Indexed<bool>> {
+        let request = pdu::write_single_coil(value);
+        let Some(channel) = self.channel() else {
+            self.request_echo(slave_id, request, 5, timeout).await?;
+            return Ok(value);
+        };
 
-        let result = channel.write_single_coil(request_param, value).await?;
-        Ok(result)
+        let mut channel = channel.clone();
+        let request_param = self.request_param(slave_id, timeout);
+
+        let exchange = self.traffic().channel_request(slave_id, request);
+        let result = channel.write_single_coil(request_param, value).await;
+        self.traffic()
+            .response(exchange, &result, |value| pdu::write_single_coil(*value));
+
+        Ok(result?)
     }
 
+    // Counts above one PDU go out as consecutive requests. They are not
+    // atomic: chunks before a failing one stay written, and the error is a
+    // `ChunkError` naming the start and count of the chunk that failed
     async fn write_multiple_coils(
         &self,
         slave_id: u8,
         start_address: u16,
         values: Vec<bool>,
+        timeout: Option<Duration>,
     ) -> Result<AddressRange> {
-        let mut channel = self.channel().clone();
-        let request_param = RequestParam::new(UnitId::new(slave_id), Duration::from_millis(1000));
-        let request = WriteMultiple::from(start_address, values)?;
+        let count = u16::try_from(values.len())
+            .map_err(|_| Error::invalid_parameter("Too many values to write"))?;
 
-        let result = channel.write_multiple_coils(request_param, request).await?;
-        Ok(result)
+        for range in split_range(start_address, count, MAX_WRITE_COILS) {
+            let offset = (range.start - start_address) as usize;
+            let chunk = values[offset..offset + range.count as usize].to_vec();
+            self.request_write_coils(slave_id, range.start, chunk, timeout)
+                .await
+                .map_err(|e| chunk_error(range, count > MAX_WRITE_COILS, e))?;
+        }
+
+        Ok(AddressRange {
+            start: start_address,
+            count,
+        })
     }
 
     async fn write_single_register(
         &self,
         slave_id: u8,
         value: Indexed<u16>,
+        timeout: Option<Duration>,
     ) -> Result<Indexed<u16>> {
-        let mut channel = self.channel().clone();
-        let request_param = RequestParam::new(UnitId::new(slave_id), Duration::from_millis(1000));
+        let request = pdu::write_single_register(value);
+        let Some(channel) = self.channel() else {
+            self.request_echo(slave_id, request, 5, timeout).await?;
+            return Ok(value);
+        };
 
-        let result = channel.write_single_register(request_param, value).await?;
-        Ok(result)
+        let mut channel = channel.clone();
+        let request_param = self.request_param(slave_id, timeout);
+
+        let exchange = self.traffic().channel_request(slave_id, request);
+        let result = channel.write_single_register(request_param, value).await;
+        self.traffic().response(exchange, &result, |value| {
+            pdu::write_single_register(*value)
+        });
+
+        Ok(result?)
     }
 
+    // Chunked like `write_multiple_coils`, with the same partial writes on failure
     async fn write_multiple_registers(
         &self,
         slave_id: u8,
         start_address: u16,
         values: Vec<u16>,
+        timeout: Option<Duration>,
     ) -> Result<AddressRange> {
-        let mut channel = self.channel().clone();
-        let request_param = RequestParam::new(UnitId::new(slave_id), Duration::from_millis(1000));
-        let request = WriteMultiple::from(start_address, values)?;
+        let count = u16::try_from(values.len())
+            .map_err(|_| Error::invalid_parameter("Too many values to write"))?;
+
+        for range in split_range(start_address, count, MAX_WRITE_REGISTERS) {
+            let offset = (range.start - start_address) as usize;
+            let chunk = values[offset..offset + range.count as usize].to_vec();
+            self.request_write_registers(slave_id, range.start, chunk, timeout)
+                .await
+                .map_err(|e| chunk_error(range, count > MAX_WRITE_REGISTERS, e))?;
+        }
+
+        Ok(AddressRange {
+            start: start_address,
+            count,
+        })
+    }
+
+    async fn read_write_multiple_registers(
+        &self,
+        slave_id: u8,
+        read_address: u16,
+        read_count: u16,
+        write_address: u16,
+        values: Vec<u16>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<Indexed<u16>>> {
+        let read_range = AddressRange {
+            start: read_address,
+            count: read_count,
+        };
+        pdu::check_request_range(read_range, MAX_READ_WRITE_READ_REGISTERS)?;
+        let write_count = u16::try_from(values.len())
+            .map_err(|_| Error::invalid_parameter("Too many values to write"))?;
+        pdu::check_request_range(
+            AddressRange {
+                start: write_address,
+                count: write_count,
+            },
+            MAX_READ_WRITE_WRITE_REGISTERS,
+        )?;
+        let request = pdu::read_write_multiple_registers(read_range, write_address, &values);
+        let response = self.request_pdu(slave_id, request, timeout).await?;
+
+        pdu::registers_from_response(read_range, &response)
+    }
+
+    async fn mask_write_register(
+        &self,
+        slave_id: u8,
+        address: u16,
+        and_mask: u16,
+        or_mask: u16,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let request = pdu::mask_write_register(address, and_mask, or_mask);
+        let response = self.request_pdu(slave_id, request.clone(), timeout).await?;
+
+        if response != request {
+            return Err(pdu::invalid_response("Mask write was not echoed"));
+        }
+        Ok(())
+    }
+
+    async fn read_fifo_queue(
+        &self,
+        slave_id: u8,
+        pointer_address: u16,
+        timeout: Option<Duration>,
+    ) -> Result<pdu::FifoQueue> {
+        let request = pdu::read_fifo_queue(pointer_address);
+        let response = self.request_pdu(slave_id, request, timeout).await?;
+
+        pdu::parse_fifo_queue(&response)
+    }
+
+    async fn read_file_record(
+        &self,
+        slave_id: u8,
+        requests: Vec<FileRecordRequest>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<FileRecord>> {
+        let request = file_record::read_file_record(&requests)?;
+        let response = self.request_pdu(slave_id, request, timeout).await?;
+
+        file_record::parse_read_file_record(&requests, &response)
+    }
+
+    async fn write_file_record(
+        &self,
+        slave_id: u8,
+        records: Vec<FileRecord>,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let request = file_record::write_file_record(&records)?;
+        let response = self.request_pdu(slave_id, request.clone(), timeout).await?;
+
+        if response != request {
+            return Err(pdu::invalid_response("File record write was not echoed"));
+        }
+        Ok(())
+    }
+
+    // Follows "more follows" continuations until all objects of the
+    // category are read; individual access returns a single object
+    async fn read_device_identification(
+        &self,
+        slave_id: u8,
+        category: DeviceIdCategory,
+        object_id: u8,
+        timeout: Option<Duration>,
+    ) -> Result<DeviceIdentification> {
+        let mut identification = DeviceIdentification {
+            conformity_level: 0,
+            objects: Vec::new(),
+        };
+        let mut next_object_id = Some(object_id);
+
+        while let Some(object_id) = next_object_id {
+            let request = identification::read_device_identification(category, object_id);
+            let response = self.request_pdu(slave_id, request, timeout).await?;
+            let part = identification::parse_device_identification(&response)?;
+
+            identification.conformity_level = part.conformity_level;
+            identification.objects.extend(part.objects);
+            next_object_id = match part.next_object_id {
+                _ if category == DeviceIdCategory::Individual => None,
+                Some(next) if next <= object_id => {
+                    return Err(pdu::invalid_response("Next object ID does not advance"))
+                }
+                next => next,
+            };
+        }
+
+        Ok(identification)
+    }
 
-        let result = channel
-            .write_multiple_registers(request_param, request)
+    async fn report_server_id(
+        &self,
+        slave_id: u8,
+        timeout: Option<Duration>,
+    ) -> Result<ServerIdReport> {
+        let response = self
+            .request_pdu(slave_id, vec![pdu::REPORT_SERVER_ID], timeout)
             .await?;
-        Ok(result)
+
+        identification::parse_report_server_id(&response)
     }
 }
 
 pub struct ModbusConnectionRTU {
     channel: Option<Channel>,
+    // Function codes rodbus does not implement
+    transport: Option<SharedTransport>,
+    timeout: Duration,
+    traffic: Arc<TrafficLog>,
+    status: Arc<ConnectionStatusTracker>,
 }
 
 impl ModbusConnectionRTU {
-    pub fn new() -> Self {
-        Self { channel: None }
+    pub fn new(traffic: Arc<TrafficLog>, status: Arc<ConnectionStatusTracker>) -> Self {
+        Self {
+            channel: None,
+            transport: None,
+            timeout: DEFAULT_TIMEOUT,
+            traffic,
+            status,
+        }
     }
 }
 
 impl HasChannel for ModbusConnectionRTU {
-    fn channel(&self) -> &Channel {
-        self.channel.as_ref().expect("Connection not established")
+    fn channel(&self) -> Option<&Channel> {
+        self.channel.as_ref()
     }
 }
 
@@ -179,9 +823,21 @@ impl ModbusConnectionTrait for ModbusConnectionRTU {
                 data_bits,
                 parity,
                 stop_bits,
+                timeout,
+                decode_level,
             } => {
+                let settings = SerialSettings {
+                    path,
+                    baud_rate,
+                    data_bits,
+                    parity,
+                    stop_bits,
+                };
+                // Fail early on a missing or busy port, rather than leaving
+                // the channel to retry it in the background
+                settings.open()?;
                 let channel = spawn_rtu_client_task(
-                    path.as_str(),
+                    settings.path.as_str(),
                     rodbus::SerialSettings {
                         baud_rate,
                         data_bits,
@@ -191,11 +847,14 @@ impl ModbusConnectionTrait for ModbusConnectionRTU {
                     },
                     1,
                     default_retry_strategy(),
-                    DecodeLevel::default(),
-                    None,
+                    decode_level,
+                    Some(Box::new(PortStateListener(self.status.clone()))),
                 );
                 channel.enable().await?;
+                self.transport = Some(shared(ChannelPortTransport::new(channel.clone(), settings)));
                 self.channel = Some(channel);
+                self.traffic.set_decode_level(decode_level.app);
+                self.timeout = timeout;
                 Ok(())
             }
             _ => Err(Error::ModbusError(
@@ -203,21 +862,132 @@ impl ModbusConnectionTrait for ModbusConnectionRTU {
             )),
         }
     }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn traffic(&self) -> &TrafficLog {
+        &self.traffic
+    }
+
+    fn serial_diagnostics(&self) -> Option<&dyn SerialDiagnostics> {
+        Some(self)
+    }
+
+    fn transport(&self) -> Option<&SharedTransport> {
+        self.transport.as_ref()
+    }
+}
+
+impl SerialDiagnostics for ModbusConnectionRTU {}
+
+pub struct ModbusConnectionASCII {
+    transport: Option<SharedTransport>,
+    timeout: Duration,
+    traffic: Arc<TrafficLog>,
+    status: Arc<ConnectionStatusTracker>,
+}
+
+impl ModbusConnectionASCII {
+    pub fn new(traffic: Arc<TrafficLog>, status: Arc<ConnectionStatusTracker>) -> Self {
+        Self {
+            transport: None,
+            timeout: DEFAULT_TIMEOUT,
+            traffic,
+            status,
+        }
+    }
+}
+
+// rodbus has no ASCII client, so all requests are raw PDUs
+impl HasChannel for ModbusConnectionASCII {
+    fn channel(&self) -> Option<&Channel> {
+        None
+    }
 }
 
+#[async_trait::async_trait]
+impl ModbusConnectionTrait for ModbusConnectionASCII {
+    async fn establish_connection(
+        &mut self,
+        connection_settings: ModbusConnectionSettings,
+    ) -> Result<()> {
+        match connection_settings {
+            ModbusConnectionSettings::ASCII {
+                path,
+                baud_rate,
+                data_bits,
+                parity,
+                stop_bits,
+                timeout,
+            } => {
+                let settings = SerialSettings {
+                    path,
+                    baud_rate,
+                    data_bits,
+                    parity,
+                    stop_bits,
+                };
+                // Fail early on a missing or busy port
+                settings.open()?;
+                self.transport = Some(shared(AsciiTransport::new(settings)));
+                self.timeout = timeout;
+                Ok(())
+            }
+            _ => Err(Error::ModbusError(
+                ModbusError::MismatchedConnectionSettings,
+            )),
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn traffic(&self) -> &TrafficLog {
+        &self.traffic
+    }
+
+    fn serial_diagnostics(&self) -> Option<&dyn SerialDiagnostics> {
+        Some(self)
+    }
+
+    fn transport(&self) -> Option<&SharedTransport> {
+        self.transport.as_ref()
+    }
+
+    fn status(&self) -> Option<&ConnectionStatusTracker> {
+        Some(&self.status)
+    }
+}
+
+impl SerialDiagnostics for ModbusConnectionASCII {}
+
 pub struct ModbusConnectionTCP {
     channel: Option<Channel>,
+    // Function codes rodbus does not implement, on a second session
+    transport: Option<SharedTransport>,
+    timeout: Duration,
+    traffic: Arc<TrafficLog>,
+    status: Arc<ConnectionStatusTracker>,
 }
 
 impl ModbusConnectionTCP {
-    pub fn new() -> Self {
-        Self { channel: None }
+    pub fn new(traffic: Arc<TrafficLog>, status: Arc<ConnectionStatusTracker>) -> Self {
+        Self {
+            channel: None,
+            transport: None,
+            timeout: DEFAULT_TIMEOUT,
+            traffic,
+            status,
+        }
     }
 }
 
 impl HasChannel for ModbusConnectionTCP {
-    fn channel(&self) -> &Channel {
-        self.channel.as_ref().expect("Connection not established")
+    fn channel(&self) -> Option<&Channel> {
+        self.channel.as_ref()
     }
 }
 
@@ -228,17 +998,239 @@ impl ModbusConnectionTrait for ModbusConnectionTCP {
         connection_settings: ModbusConnectionSettings,
     ) -> Result<()> {
         match connection_settings {
-            ModbusConnectionSettings::TCP { host, port } => {
+            ModbusConnectionSettings::TCP {
+                host,
+                port,
+                timeout,
+                decode_level,
+            } => {
+                let ip = host.parse()?;
                 let channel = spawn_tcp_client_task(
-                    HostAddr::ip(host.parse()?, port),
+                    HostAddr::ip(ip, port),
                     1,
                     default_retry_strategy(),
-                    DecodeLevel::default(),
-                    None,
+                    decode_level,
+                    Some(Box::new(ClientStateListener {
+                        status: self.status.clone(),
+                        forward: None,
+                    })),
                 );
+                channel.enable().await?;
+                self.channel = Some(channel);
+                self.transport = Some(shared(TcpTransport::new(SocketAddr::new(ip, port))));
+                self.traffic.set_decode_level(decode_level.app);
+                self.timeout = timeout;
+
+                Ok(())
+            }
+            _ => Err(Error::ModbusError(
+                ModbusError::MismatchedConnectionSettings,
+            )),
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn traffic(&self) -> &TrafficLog {
+        &self.traffic
+    }
+
+    fn transport(&self) -> Option<&SharedTransport> {
+        self.transport.as_ref()
+    }
+}
+
+// Reports rodbus channel states to the connection status, optionally
+// forwarding them so connection attempts can be awaited
+struct ClientStateListener {
+    status: Arc<ConnectionStatusTracker>,
+    forward: Option<watch::Sender<ClientState>>,
+}
+
+impl Listener<ClientState> for ClientStateListener {
+    fn update(&mut self, value: ClientState) -> MaybeAsync<()> {
+        self.status.client_state(&value);
+        if let Some(forward) = &self.forward {
+            let _ = forward.send(value);
+        }
+        MaybeAsync::ready(())
+    }
+}
+
+struct PortStateListener(Arc<ConnectionStatusTracker>);
+
+impl Listener<PortState> for PortStateListener {
+    fn update(&mut self, value: PortState) -> MaybeAsync<()> {
+        self.0.port_state(&value);
+        MaybeAsync::ready(())
+    }
+}
+
+pub struct ModbusConnectionTLS {
+    channel: Option<Channel>,
+    timeout: Duration,
+    traffic: Arc<TrafficLog>,
+    status: Arc<ConnectionStatusTracker>,
+}
+
+impl ModbusConnectionTLS {
+    pub fn new(traffic: Arc<TrafficLog>, status: Arc<ConnectionStatusTracker>) -> Self {
+        Self {
+            channel: None,
+            timeout: DEFAULT_TIMEOUT,
+            traffic,
+            status,
+        }
+    }
+}
 
+impl HasChannel for ModbusConnectionTLS {
+    fn channel(&self) -> Option<&Channel> {
+        self.channel.as_ref()
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusConnectionTrait for ModbusConnectionTLS {
+    async fn establish_connection(
+        &mut self,
+        connection_settings: ModbusConnectionSettings,
+    ) -> Result<()> {
+        match connection_settings {
+            ModbusConnectionSettings::TLS {
+                host,
+                port,
+                timeout,
+                decode_level,
+                server_name,
+                ca_path,
+                cert_path,
+                key_path,
+                key_password,
+            } => {
+                let ip = host.parse()?;
+                let tls_config = TlsClientConfig::full_pki(
+                    Some(server_name.clone()),
+                    Path::new(&ca_path),
+                    Path::new(&cert_path),
+                    Path::new(&key_path),
+                    key_password.as_deref(),
+                    MinTlsVersion::V1_2,
+                )
+                .map_err(|e| ModbusError::TlsConfig(e.to_string()))?;
+
+                let (state_tx, mut state_rx) = watch::channel(ClientState::Disabled);
+                let channel = spawn_tls_client_task(
+                    HostAddr::ip(ip, port),
+                    1,
+                    default_retry_strategy(),
+                    tls_config,
+                    decode_level,
+                    Some(Box::new(ClientStateListener {
+                        status: self.status.clone(),
+                        forward: Some(state_tx),
+                    })),
+                );
                 channel.enable().await?;
+
+                // A slow server is a timeout; only a failed attempt means the
+                // connection or the handshake was rejected. The channel task
+                // stops when the channel is dropped on error.
+                let connected = tokio::time::timeout(
+                    timeout,
+                    state_rx.wait_for(|state| {
+                        matches!(
+                            state,
+                            ClientState::Connected | ClientState::WaitAfterFailedConnect(_)
+                        )
+                    }),
+                )
+                .await
+                .map_err(|_| ModbusError::RequestError(RequestError::ResponseTimeout))?
+                .map(|state| matches!(*state, ClientState::Connected))
+                .map_err(|_| ModbusError::RequestError(RequestError::Shutdown))?;
+                if !connected {
+                    // rodbus does not say which step failed
+                    return Err(Error::ModbusError(ModbusError::TlsHandshake(format!(
+                        "{}:{} refused the connection or the session; check that it is reachable, and the CA, client certificate, key and server name {}",
+                        host, port, server_name
+                    ))));
+                }
+
                 self.channel = Some(channel);
+                self.timeout = timeout;
+
+                Ok(())
+            }
+            _ => Err(Error::ModbusError(
+                ModbusError::MismatchedConnectionSettings,
+            )),
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn traffic(&self) -> &TrafficLog {
+        &self.traffic
+    }
+
+    // rodbus only sends the function codes it implements and a second
+    // session would need its own TLS stack, so raw PDUs (diagnostics, file
+    // records, identification, custom function codes, the gateway) are
+    // rejected on TLS connections
+    fn transport(&self) -> Option<&SharedTransport> {
+        None
+    }
+}
+
+pub struct ModbusConnectionRTUOverTCP {
+    transport: Option<SharedTransport>,
+    timeout: Duration,
+    traffic: Arc<TrafficLog>,
+    status: Arc<ConnectionStatusTracker>,
+}
+
+impl ModbusConnectionRTUOverTCP {
+    pub fn new(traffic: Arc<TrafficLog>, status: Arc<ConnectionStatusTracker>) -> Self {
+        Self {
+            transport: None,
+            timeout: DEFAULT_TIMEOUT,
+            traffic,
+            status,
+        }
+    }
+}
+
+// rodbus has no RTU-over-TCP client, so all requests are raw PDUs
+impl HasChannel for ModbusConnectionRTUOverTCP {
+    fn channel(&self) -> Option<&Channel> {
+        None
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusConnectionTrait for ModbusConnectionRTUOverTCP {
+    async fn establish_connection(
+        &mut self,
+        connection_settings: ModbusConnectionSettings,
+    ) -> Result<()> {
+        match connection_settings {
+            ModbusConnectionSettings::RTUOverTCP {
+                host,
+                port,
+                timeout,
+                frame_gap,
+            } => {
+                let ip = host.parse()?;
+                self.transport = Some(shared(RtuOverTcpTransport::new(
+                    SocketAddr::new(ip, port),
+                    frame_gap,
+                )));
+                self.timeout = timeout;
 
                 Ok(())
             }
@@ -247,4 +1239,331 @@ impl ModbusConnectionTrait for ModbusConnectionTCP {
             )),
         }
     }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn traffic(&self) -> &TrafficLog {
+        &self.traffic
+    }
+
+    // The gateway forwards to a serial bus
+    fn serial_diagnostics(&self) -> Option<&dyn SerialDiagnostics> {
+        Some(self)
+    }
+
+    fn transport(&self) -> Option<&SharedTransport> {
+        self.transport.as_ref()
+    }
+
+    fn status(&self) -> Option<&ConnectionStatusTracker> {
+        Some(&self.status)
+    }
+}
+
+impl SerialDiagnostics for ModbusConnectionRTUOverTCP {}
+
+pub struct ModbusConnectionUDP {
+    transport: Option<SharedTransport>,
+    timeout: Duration,
+    traffic: Arc<TrafficLog>,
+    status: Arc<ConnectionStatusTracker>,
+}
+
+impl ModbusConnectionUDP {
+    pub fn new(traffic: Arc<TrafficLog>, status: Arc<ConnectionStatusTracker>) -> Self {
+        Self {
+            transport: None,
+            timeout: DEFAULT_TIMEOUT,
+            traffic,
+            status,
+        }
+    }
+}
+
+// rodbus has no UDP client, so all requests are raw PDUs
+impl HasChannel for ModbusConnectionUDP {
+    fn channel(&self) -> Option<&Channel> {
+        None
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusConnectionTrait for ModbusConnectionUDP {
+    async fn establish_connection(
+        &mut self,
+        connection_settings: ModbusConnectionSettings,
+    ) -> Result<()> {
+        match connection_settings {
+            ModbusConnectionSettings::UDP {
+                host,
+                port,
+                timeout,
+                retransmissions,
+            } => {
+                let ip = host.parse()?;
+                self.transport = Some(shared(UdpTransport::new(
+                    SocketAddr::new(ip, port),
+                    retransmissions,
+                )));
+                self.timeout = timeout;
+
+                Ok(())
+            }
+            _ => Err(Error::ModbusError(
+                ModbusError::MismatchedConnectionSettings,
+            )),
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn traffic(&self) -> &TrafficLog {
+        &self.traffic
+    }
+
+    fn transport(&self) -> Option<&SharedTransport> {
+        self.transport.as_ref()
+    }
+
+    fn status(&self) -> Option<&ConnectionStatusTracker> {
+        Some(&self.status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::status::{ConnectionState, TIMEOUTS_BEFORE_RETRYING};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    // Stands in for a gateway by echoing every frame back, split into two
+    // delayed segments
+    async fn spawn_echo_gateway() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 256];
+            loop {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                let (first, second) = buffer[..read].split_at(read / 2);
+                socket.write_all(first).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                socket.write_all(second).await.unwrap();
+            }
+        });
+
+        addr
+    }
+
+    fn ranges(start: u16, count: u16, max: u16) -> Vec<(u16, u16)> {
+        split_range(start, count, max)
+            .iter()
+            .map(|range| (range.start, range.count))
+            .collect()
+    }
+
+    #[test]
+    fn split_range_keeps_empty_and_single_requests() {
+        assert_eq!(ranges(10, 0, MAX_WRITE_REGISTERS), vec![(10, 0)]);
+        assert_eq!(ranges(0, 1, MAX_WRITE_REGISTERS), vec![(0, 1)]);
+        assert_eq!(
+            ranges(100, MAX_WRITE_REGISTERS, MAX_WRITE_REGISTERS),
+            vec![(100, 123)]
+        );
+        assert_eq!(
+            ranges(100, MAX_WRITE_COILS, MAX_WRITE_COILS),
+            vec![(100, 1968)]
+        );
+    }
+
+    #[test]
+    fn split_range_splits_one_past_the_limit() {
+        assert_eq!(
+            ranges(0, MAX_WRITE_REGISTERS + 1, MAX_WRITE_REGISTERS),
+            vec![(0, 123), (123, 1)]
+        );
+        assert_eq!(
+            ranges(5, MAX_WRITE_COILS + 1, MAX_WRITE_COILS),
+            vec![(5, 1968), (1973, 1)]
+        );
+        assert_eq!(
+            ranges(0, 2 * MAX_READ_REGISTERS, MAX_READ_REGISTERS),
+            vec![(0, 125), (125, 125)]
+        );
+    }
+
+    #[test]
+    fn split_range_at_the_top_of_the_address_space() {
+        // Ends exactly at 0xFFFF
+        assert_eq!(
+            ranges(0xFFFF - 199, 200, MAX_WRITE_REGISTERS),
+            vec![(0xFF38, 123), (0xFFB3, 77)]
+        );
+        assert_eq!(ranges(0xFFFF, 1, MAX_WRITE_REGISTERS), vec![(0xFFFF, 1)]);
+        // Past the end stays a single range for the request to reject
+        assert_eq!(
+            ranges(0xFFFF, 200, MAX_WRITE_REGISTERS),
+            vec![(0xFFFF, 200)]
+        );
+    }
+
+    #[test]
+    fn chunk_error_names_the_failing_chunk() {
+        let range = AddressRange {
+            start: 123,
+            count: 1,
+        };
+        let err = chunk_error(range, true, Error::invalid_parameter("boom"));
+        assert!(matches!(
+            err,
+            Error::ModbusError(ModbusError::ChunkError {
+                start: 123,
+                count: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            chunk_error(range, false, Error::invalid_parameter("boom")),
+            Error::ModbusError(ModbusError::InvalidParameter(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn invalid_ranges_never_reach_the_wire() {
+        let addr = spawn_echo_gateway().await;
+        let traffic = Arc::new(TrafficLog::new(1, None));
+        let status = Arc::new(ConnectionStatusTracker::new(1, None));
+        let mut connection = ModbusConnectionRTUOverTCP::new(traffic.clone(), status);
+        connection
+            .establish_connection(ModbusConnectionSettings::RTUOverTCP {
+                host: addr.ip().to_string(),
+                port: addr.port(),
+                timeout: Duration::from_secs(1),
+                frame_gap: Duration::from_millis(50),
+            })
+            .await
+            .unwrap();
+
+        let is_invalid = |result: Result<Vec<Indexed<u16>>>| {
+            matches!(
+                result,
+                Err(Error::ModbusError(ModbusError::InvalidParameter(_)))
+            )
+        };
+        assert!(is_invalid(
+            connection.read_holding_registers(1, 0, 0, None).await
+        ));
+        assert!(is_invalid(
+            connection
+                .read_holding_registers(1, 0xFFF0, 100, None)
+                .await
+        ));
+        assert!(is_invalid(
+            connection
+                .read_write_multiple_registers(1, 0, 1, 0, vec![0; 122], None)
+                .await
+        ));
+        assert!(connection
+            .write_multiple_registers(1, 0xFFFF, vec![1, 2], None)
+            .await
+            .is_err());
+        assert!(traffic.records().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rtu_over_tcp_reassembles_split_frames() {
+        let addr = spawn_echo_gateway().await;
+        let traffic = Arc::new(TrafficLog::new(1, None));
+        let status = Arc::new(ConnectionStatusTracker::new(1, None));
+        let mut connection = ModbusConnectionRTUOverTCP::new(traffic.clone(), status.clone());
+        connection
+            .establish_connection(ModbusConnectionSettings::RTUOverTCP {
+                host: addr.ip().to_string(),
+                port: addr.port(),
+                timeout: Duration::from_secs(1),
+                frame_gap: Duration::from_millis(50),
+            })
+            .await
+            .unwrap();
+
+        // Write Single Register responses echo the request
+        let written = connection
+            .write_single_register(7, Indexed::new(10, 0x1234), None)
+            .await
+            .unwrap();
+        assert_eq!(written.index, 10);
+        assert_eq!(written.value, 0x1234);
+
+        let records = traffic.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].unit_id, 7);
+        assert_eq!(records[1].raw, [0x06, 0x00, 0x0A, 0x12, 0x34]);
+        assert!(records[1].error.is_none());
+        assert!(!records[1].reconstructed);
+        assert_eq!(status.status().state, ConnectionState::Connected);
+    }
+
+    #[tokio::test]
+    async fn rtu_over_tcp_reports_lost_link() {
+        // Nothing listens on the port once the listener is dropped
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let status = Arc::new(ConnectionStatusTracker::new(1, None));
+        let mut connection =
+            ModbusConnectionRTUOverTCP::new(Arc::new(TrafficLog::new(1, None)), status.clone());
+        connection
+            .establish_connection(ModbusConnectionSettings::RTUOverTCP {
+                host: addr.ip().to_string(),
+                port: addr.port(),
+                timeout: Duration::from_secs(1),
+                frame_gap: Duration::from_millis(50),
+            })
+            .await
+            .unwrap();
+
+        assert!(connection.read_coils(1, 1, 1, None).await.is_err());
+        assert_eq!(status.status().state, ConnectionState::Retrying);
+    }
+
+    #[tokio::test]
+    async fn udp_reports_lost_link_after_repeated_timeouts() {
+        // Bound but never answering, like an unreachable host
+        let silent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = silent.local_addr().unwrap();
+        let status = Arc::new(ConnectionStatusTracker::new(1, None));
+        let mut connection =
+            ModbusConnectionUDP::new(Arc::new(TrafficLog::new(1, None)), status.clone());
+        connection
+            .establish_connection(ModbusConnectionSettings::UDP {
+                host: addr.ip().to_string(),
+                port: addr.port(),
+                timeout: Duration::from_millis(20),
+                retransmissions: 0,
+            })
+            .await
+            .unwrap();
+        status.set(ConnectionState::Connected, None);
+
+        for _ in 1..TIMEOUTS_BEFORE_RETRYING {
+            assert!(connection.read_coils(1, 1, 1, None).await.is_err());
+            assert_eq!(status.status().state, ConnectionState::Connected);
+        }
+        assert!(connection.read_coils(1, 1, 1, None).await.is_err());
+        assert_eq!(status.status().state, ConnectionState::Retrying);
+    }
 }
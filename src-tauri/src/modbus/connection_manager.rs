@@ -1,34 +1,118 @@
 use std::{collections::HashMap, sync::Arc};
 
+use tauri::AppHandle;
 use tokio::sync::RwLock;
 
-use crate::modbus::ModbusConnectionTrait;
+use crate::modbus::{
+    poller::{PollJob, PollJobId, Poller},
+    sniffer::Sniffer,
+    status::{ConnectionState, ConnectionStatus, ConnectionStatusTracker},
+    ModbusConnectionTrait,
+};
 
 pub type ConnectionId = u32;
 
 pub struct ModbusConnectionManager {
     connections: RwLock<HashMap<ConnectionId, Arc<dyn ModbusConnectionTrait>>>,
+    // Device identity of each connection, see ModbusConnectionSettings::identity
+    identities: RwLock<HashMap<ConnectionId, String>>,
+    pollers: RwLock<HashMap<ConnectionId, HashMap<PollJobId, Poller>>>,
+    // Listen-only captures, sharing IDs with connections
+    sniffers: RwLock<HashMap<ConnectionId, Sniffer>>,
+    // Tracked from the first connection attempt, failed and closed
+    // connections included
+    statuses: RwLock<HashMap<ConnectionId, Arc<ConnectionStatusTracker>>>,
 }
 
 impl ModbusConnectionManager {
     pub fn new() -> Self {
         Self {
             connections: RwLock::new(HashMap::new()),
+            identities: RwLock::new(HashMap::new()),
+            pollers: RwLock::new(HashMap::new()),
+            sniffers: RwLock::new(HashMap::new()),
+            statuses: RwLock::new(HashMap::new()),
         }
     }
     pub async fn add_connection(
         &self,
         id: ConnectionId,
+        identity: String,
         connection: Arc<dyn ModbusConnectionTrait>,
     ) {
+        self.identities.write().await.insert(id, identity);
         self.connections.write().await.insert(id, connection);
     }
 
     pub async fn remove_connection(&self, id: ConnectionId) {
+        self.pollers.write().await.remove(&id);
+        self.sniffers.write().await.remove(&id);
+        // The tracker stays so the closed state can still be queried; the
+        // next attempt with this ID replaces it
+        if let Some(status) = self.statuses.read().await.get(&id) {
+            status.set(ConnectionState::Closed, None);
+        }
         self.connections.write().await.remove(&id);
+        self.identities.write().await.remove(&id);
     }
 
     pub async fn get_connection(&self, id: ConnectionId) -> Option<Arc<dyn ModbusConnectionTrait>> {
         self.connections.read().await.get(&id).cloned()
     }
+
+    pub async fn identity(&self, id: ConnectionId) -> Option<String> {
+        self.identities.read().await.get(&id).cloned()
+    }
+
+    // Starts tracking a new connection attempt in the connecting state
+    pub async fn track_status(
+        &self,
+        app: AppHandle,
+        id: ConnectionId,
+    ) -> Arc<ConnectionStatusTracker> {
+        let status = Arc::new(ConnectionStatusTracker::new(id, Some(app)));
+        self.statuses.write().await.insert(id, status.clone());
+
+        status
+    }
+
+    pub async fn connection_status(&self, id: ConnectionId) -> Option<ConnectionStatus> {
+        self.statuses
+            .read()
+            .await
+            .get(&id)
+            .map(|status| status.status())
+    }
+
+    // Starting a job with an existing id replaces its configuration
+    pub async fn start_poll_job(&self, app: AppHandle, id: ConnectionId, job: PollJob) -> bool {
+        let Some(connection) = self.get_connection(id).await else {
+            return false;
+        };
+
+        let job_id = job.id;
+        let poller = Poller::spawn(app, id, connection, job);
+        self.pollers
+            .write()
+            .await
+            .entry(id)
+            .or_default()
+            .insert(job_id, poller);
+
+        true
+    }
+
+    pub async fn stop_poll_job(&self, id: ConnectionId, job_id: PollJobId) {
+        if let Some(jobs) = self.pollers.write().await.get_mut(&id) {
+            jobs.remove(&job_id);
+        }
+    }
+
+    pub async fn add_sniffer(&self, id: ConnectionId, sniffer: Sniffer) {
+        self.sniffers.write().await.insert(id, sniffer);
+    }
+
+    pub async fn remove_sniffer(&self, id: ConnectionId) {
+        self.sniffers.write().await.remove(&id);
+    }
 }
@@ -1,27 +1,77 @@
-use std::sync::Arc;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
 
 use crate::{
     app_state::registry::AppState,
+    errors::{CommandError, Error},
     modbus::{
-        connection::{ModbusConnectionRTU, ModbusConnectionSettings, ModbusConnectionTCP},
+        connection::{
+            ModbusConnectionASCII, ModbusConnectionRTU, ModbusConnectionRTUOverTCP,
+            ModbusConnectionSettings, ModbusConnectionTCP, ModbusConnectionTLS,
+            ModbusConnectionUDP, RegisterType,
+        },
+        decoding::{self, ByteOrder, DataType, DecodedValue, TypedValue},
+        diagnostics::{CommEventCounter, CommEventLog, DiagnosticCounters},
+        export,
+        file_record::{FileRecord, FileRecordRequest},
+        gateway::{GatewayRoute, ModbusGateway},
+        identification::{DeviceIdCategory, DeviceIdentification, ServerIdReport},
+        pdu::{FifoQueue, RawResponse},
+        planner,
+        poller::PollJob,
+        server::{ModbusServer, ModbusServerSettings},
+        sniffer::{Sniffer, DEFAULT_SNIFFER_MIN_GAP},
+        status::{ConnectionState, ConnectionStatus, ConnectionStatusTracker},
+        traffic::{TrafficLog, TrafficRecord},
+        transport::{SerialSettings, DEFAULT_RTU_OVER_TCP_FRAME_GAP, DEFAULT_UDP_RETRANSMISSIONS},
         ModbusConnectionTrait,
     },
 };
 use log::info;
-use rodbus::{DataBits, Indexed, Parity, StopBits};
+use rodbus::{
+    AppDecodeLevel, DataBits, DecodeLevel, FrameDecodeLevel, Indexed, Parity, PhysDecodeLevel,
+    StopBits,
+};
 use serde::Serialize;
-use tauri::State;
+use specta::Type;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub async fn close_connection(app_state: State<'_, AppState>, id: u32) -> Result<(), String> {
+#[specta::specta]
+pub async fn close_connection(app_state: State<'_, AppState>, id: u32) -> Result<(), CommandError> {
+    // Gateways hold the connection, which would otherwise stay open
+    app_state
+        .server_manager()
+        .remove_connection_gateways(id)
+        .await;
     app_state.connection_manager().remove_connection(id).await;
 
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn get_connection_status(
+    app_state: State<'_, AppState>,
+    id: u32,
+) -> Result<ConnectionStatus, CommandError> {
+    let status = app_state
+        .connection_manager()
+        .connection_status(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+
+    Ok(status)
+}
+
 // Modbus RTU Controller
 #[tauri::command]
+#[specta::specta]
 pub async fn connect_modbus_rtu(
+    app: AppHandle,
     app_state: State<'_, AppState>,
     id: u32,
     path: String,
@@ -29,124 +79,383 @@ pub async fn connect_modbus_rtu(
     data_bits: u8,
     parity: String,
     stop_bits: u8,
+    timeout: u64,
     retries: u32,
-) -> Result<(), String> {
+    decode_level: Option<String>,
+) -> Result<(), CommandError> {
     info!("Connecting to Modbus RTU device at {}", path);
 
-    let mut connection = ModbusConnectionRTU::new();
+    let (data_bits, parity, stop_bits) = parse_serial_settings(data_bits, parity, stop_bits)?;
 
-    let data_bits = match data_bits {
-        5 => DataBits::Five,
-        6 => DataBits::Six,
-        7 => DataBits::Seven,
-        8 => DataBits::Eight,
-        _ => return Err("Invalid data bits".to_string()),
+    let settings = ModbusConnectionSettings::RTU {
+        path,
+        baud_rate,
+        data_bits,
+        parity,
+        stop_bits,
+        timeout: Duration::from_millis(timeout),
+        decode_level: parse_decode_level(decode_level.as_deref())?,
     };
+    let status = app_state
+        .connection_manager()
+        .track_status(app.clone(), id)
+        .await;
+    let connection =
+        ModbusConnectionRTU::new(Arc::new(TrafficLog::new(id, Some(app))), status.clone());
 
-    let parity = match parity.as_str() {
-        "none" => Parity::None,
-        "even" => Parity::Even,
-        "odd" => Parity::Odd,
-        _ => return Err("Invalid parity".to_string()),
-    };
+    connect(&app_state, id, &status, connection, settings, retries).await
+}
 
-    let stop_bits = match stop_bits {
-        1 => StopBits::One,
-        2 => StopBits::Two,
-        _ => return Err("Invalid stop bits".to_string()),
+// Modbus ASCII Controller
+#[tauri::command]
+#[specta::specta]
+pub async fn connect_modbus_ascii(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    id: u32,
+    path: String,
+    baud_rate: u32,
+    data_bits: u8,
+    parity: String,
+    stop_bits: u8,
+    timeout: u64,
+    retries: u32,
+) -> Result<(), CommandError> {
+    info!("Connecting to Modbus ASCII device at {}", path);
+
+    let (data_bits, parity, stop_bits) = parse_serial_settings(data_bits, parity, stop_bits)?;
+
+    let settings = ModbusConnectionSettings::ASCII {
+        path,
+        baud_rate,
+        data_bits,
+        parity,
+        stop_bits,
+        timeout: Duration::from_millis(timeout),
     };
+    let status = app_state
+        .connection_manager()
+        .track_status(app.clone(), id)
+        .await;
+    let connection =
+        ModbusConnectionASCII::new(Arc::new(TrafficLog::new(id, Some(app))), status.clone());
 
-    let settings = ModbusConnectionSettings::RTU {
+    connect(&app_state, id, &status, connection, settings, retries).await
+}
+
+// Modbus RTU Sniffer Controller
+#[tauri::command]
+#[specta::specta]
+pub async fn start_modbus_sniffer(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    id: u32,
+    path: String,
+    baud_rate: u32,
+    data_bits: u8,
+    parity: String,
+    stop_bits: u8,
+    min_gap_ms: Option<u64>,
+) -> Result<(), CommandError> {
+    info!("Starting Modbus RTU sniffer on {}", path);
+    let (data_bits, parity, stop_bits) = parse_serial_settings(data_bits, parity, stop_bits)?;
+    let settings = SerialSettings {
         path,
         baud_rate,
         data_bits,
         parity,
         stop_bits,
     };
+    let min_gap = min_gap_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_SNIFFER_MIN_GAP);
+    let sniffer = Sniffer::start(app, id, settings, min_gap).map_err(|e| e.on_connection(id))?;
+
+    app_state
+        .connection_manager()
+        .add_sniffer(id, sniffer)
+        .await;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_modbus_sniffer(
+    app_state: State<'_, AppState>,
+    id: u32,
+) -> Result<(), CommandError> {
+    info!("Stopping Modbus RTU sniffer {}", id);
+    app_state.connection_manager().remove_sniffer(id).await;
+
+    Ok(())
+}
+
+// Establishes the connection and probes it with a coil read before adding
+// it. Any response proves the device is reachable, so an exception (e.g. a
+// device without coils) counts as connected rather than as a failure.
+async fn connect(
+    app_state: &AppState,
+    id: u32,
+    status: &ConnectionStatusTracker,
+    mut connection: impl ModbusConnectionTrait + 'static,
+    settings: ModbusConnectionSettings,
+    retries: u32,
+) -> Result<(), CommandError> {
+    let identity = settings.identity();
     connection
         .establish_connection(settings)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| connect_error(status, id, e))?;
 
     for attempt in 1..=retries {
-        match connection.read_coils(1, 1, 1).await {
+        match connection.read_coils(1, 1, 1, None).await {
             Ok(_) => break,
+            Err(e) if e.exception_code().is_some() => {
+                info!("Device answered the probe with an exception: {}", e);
+                break;
+            }
+            Err(e) if attempt == retries => return Err(connect_error(status, id, e)),
             Err(e) => {
                 info!("Attempt {} failed: {}", attempt, e);
-                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                if attempt == retries {
-                    return Err(e.to_string());
-                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
             }
         }
     }
 
+    status.set(ConnectionState::Connected, None);
     app_state
         .connection_manager()
-        .add_connection(id, Arc::new(connection))
+        .add_connection(id, identity, Arc::new(connection))
         .await;
 
     Ok(())
 }
 
+// Reports a failed connection attempt before returning its error
+fn connect_error(status: &ConnectionStatusTracker, id: u32, err: Error) -> CommandError {
+    status.set(ConnectionState::Failed, Some(err.to_string()));
+    err.on_connection(id)
+}
+
+fn parse_serial_settings(
+    data_bits: u8,
+    parity: String,
+    stop_bits: u8,
+) -> Result<(DataBits, Parity, StopBits), Error> {
+    let data_bits = match data_bits {
+        5 => DataBits::Five,
+        6 => DataBits::Six,
+        7 => DataBits::Seven,
+        8 => DataBits::Eight,
+        _ => return Err(Error::invalid_parameter("Invalid data bits")),
+    };
+
+    let parity = match parity.as_str() {
+        "none" => Parity::None,
+        "even" => Parity::Even,
+        "odd" => Parity::Odd,
+        _ => return Err(Error::invalid_parameter("Invalid parity")),
+    };
+
+    let stop_bits = match stop_bits {
+        1 => StopBits::One,
+        2 => StopBits::Two,
+        _ => return Err(Error::invalid_parameter("Invalid stop bits")),
+    };
+
+    Ok((data_bits, parity, stop_bits))
+}
+
+fn parse_decode_level(decode_level: Option<&str>) -> Result<DecodeLevel, Error> {
+    let decode_level = match decode_level.unwrap_or("nothing") {
+        "nothing" => DecodeLevel::nothing(),
+        "function" => DecodeLevel::new(
+            AppDecodeLevel::FunctionCode,
+            FrameDecodeLevel::Nothing,
+            PhysDecodeLevel::Nothing,
+        ),
+        "headers" => DecodeLevel::new(
+            AppDecodeLevel::DataHeaders,
+            FrameDecodeLevel::Header,
+            PhysDecodeLevel::Length,
+        ),
+        "values" => DecodeLevel::new(
+            AppDecodeLevel::DataValues,
+            FrameDecodeLevel::Payload,
+            PhysDecodeLevel::Data,
+        ),
+        _ => return Err(Error::invalid_parameter("Invalid decode level")),
+    };
+
+    Ok(decode_level)
+}
+
 // Modbus TCP Controller
 #[tauri::command]
+#[specta::specta]
 pub async fn connect_modbus_tcp(
+    app: AppHandle,
     app_state: State<'_, AppState>,
     id: u32,
     host: String,
     port: u16,
+    timeout: u64,
     retries: u32,
-) -> Result<(), String> {
+    decode_level: Option<String>,
+) -> Result<(), CommandError> {
     info!("Connecting to Modbus TCP address: {}:{}", host, port);
-    let mut connection = ModbusConnectionTCP::new();
-    let settings = ModbusConnectionSettings::TCP { host, port };
+    let settings = ModbusConnectionSettings::TCP {
+        host,
+        port,
+        timeout: Duration::from_millis(timeout),
+        decode_level: parse_decode_level(decode_level.as_deref())?,
+    };
 
-    connection
-        .establish_connection(settings)
-        .await
-        .map_err(|e| e.to_string())?;
+    let status = app_state
+        .connection_manager()
+        .track_status(app.clone(), id)
+        .await;
+    let connection =
+        ModbusConnectionTCP::new(Arc::new(TrafficLog::new(id, Some(app))), status.clone());
 
-    for attempt in 1..=retries {
-        match connection.read_coils(1, 1, 1).await {
-            Ok(_) => break,
-            Err(e) => {
-                info!("Attempt {} failed: {}", attempt, e);
-                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                if attempt == retries {
-                    return Err(e.to_string());
-                }
-            }
-        }
-    }
+    connect(&app_state, id, &status, connection, settings, retries).await
+}
 
-    app_state
+// Modbus/TCP Security Controller
+#[tauri::command]
+#[specta::specta]
+pub async fn connect_modbus_tls(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    id: u32,
+    host: String,
+    port: u16,
+    timeout: u64,
+    retries: u32,
+    decode_level: Option<String>,
+    server_name: String,
+    ca_path: String,
+    cert_path: String,
+    key_path: String,
+    key_password: Option<String>,
+) -> Result<(), CommandError> {
+    info!("Connecting to Modbus TLS address: {}:{}", host, port);
+    let settings = ModbusConnectionSettings::TLS {
+        host,
+        port,
+        timeout: Duration::from_millis(timeout),
+        decode_level: parse_decode_level(decode_level.as_deref())?,
+        server_name,
+        ca_path,
+        cert_path,
+        key_path,
+        key_password,
+    };
+
+    let status = app_state
         .connection_manager()
-        .add_connection(id, Arc::new(connection))
+        .track_status(app.clone(), id)
         .await;
+    let connection =
+        ModbusConnectionTLS::new(Arc::new(TrafficLog::new(id, Some(app))), status.clone());
 
-    Ok(())
+    connect(&app_state, id, &status, connection, settings, retries).await
+}
+
+// Modbus UDP Controller
+#[tauri::command]
+#[specta::specta]
+pub async fn connect_modbus_udp(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    id: u32,
+    host: String,
+    port: u16,
+    timeout: u64,
+    retries: u32,
+    retransmissions: Option<u32>,
+) -> Result<(), CommandError> {
+    info!("Connecting to Modbus UDP address: {}:{}", host, port);
+    let settings = ModbusConnectionSettings::UDP {
+        host,
+        port,
+        timeout: Duration::from_millis(timeout),
+        retransmissions: retransmissions.unwrap_or(DEFAULT_UDP_RETRANSMISSIONS),
+    };
+
+    let status = app_state
+        .connection_manager()
+        .track_status(app.clone(), id)
+        .await;
+    let connection =
+        ModbusConnectionUDP::new(Arc::new(TrafficLog::new(id, Some(app))), status.clone());
+
+    connect(&app_state, id, &status, connection, settings, retries).await
+}
+
+// Modbus RTU-over-TCP Controller
+#[tauri::command]
+#[specta::specta]
+pub async fn connect_modbus_rtu_over_tcp(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    id: u32,
+    host: String,
+    port: u16,
+    timeout: u64,
+    retries: u32,
+    frame_gap: Option<u64>,
+) -> Result<(), CommandError> {
+    info!(
+        "Connecting to Modbus RTU-over-TCP address: {}:{}",
+        host, port
+    );
+    let settings = ModbusConnectionSettings::RTUOverTCP {
+        host,
+        port,
+        timeout: Duration::from_millis(timeout),
+        frame_gap: frame_gap
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_RTU_OVER_TCP_FRAME_GAP),
+    };
+
+    let status = app_state
+        .connection_manager()
+        .track_status(app.clone(), id)
+        .await;
+    let connection =
+        ModbusConnectionRTUOverTCP::new(Arc::new(TrafficLog::new(id, Some(app))), status.clone());
+
+    connect(&app_state, id, &status, connection, settings, retries).await
 }
 
 // Read Data Commands
 #[tauri::command]
+#[specta::specta]
 pub async fn read_coils(
     app_state: State<'_, AppState>,
     id: u32,
     start_address: u16,
     count: u16,
     slave_id: u8,
-) -> Result<Vec<IndexedValue>, String> {
+    timeout: Option<u64>,
+) -> Result<Vec<IndexedValue>, CommandError> {
     info!("Reading coils from Modbus");
     let connection = app_state
         .connection_manager()
         .get_connection(id)
         .await
-        .ok_or("Connection not found")?;
+        .ok_or(Error::connection_not_found(id))?;
     let result = connection
-        .read_coils(slave_id, start_address, count)
+        .read_coils(
+            slave_id,
+            start_address,
+            count,
+            timeout.map(Duration::from_millis),
+        )
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.on_connection(id))?;
 
     Ok(result
         .into_iter()
@@ -154,24 +463,82 @@ pub async fn read_coils(
         .collect())
 }
 
+// Device Identification (FC 43 / MEI 14)
 #[tauri::command]
+#[specta::specta]
+pub async fn read_device_identification(
+    app_state: State<'_, AppState>,
+    id: u32,
+    category: DeviceIdCategory,
+    object_id: Option<u8>,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<DeviceIdentification, CommandError> {
+    info!("Reading device identification from Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+
+    connection
+        .read_device_identification(
+            slave_id,
+            category,
+            object_id.unwrap_or_default(),
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+// Report Server ID (FC 17)
+#[tauri::command]
+#[specta::specta]
+pub async fn report_server_id(
+    app_state: State<'_, AppState>,
+    id: u32,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<ServerIdReport, CommandError> {
+    info!("Reporting server ID from Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+
+    connection
+        .report_server_id(slave_id, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
 pub async fn read_holding_registers(
     app_state: State<'_, AppState>,
     id: u32,
     start_address: u16,
     count: u16,
     slave_id: u8,
-) -> Result<Vec<IndexedValue>, String> {
+    timeout: Option<u64>,
+) -> Result<Vec<IndexedValue>, CommandError> {
     info!("Reading holding registers from Modbus");
     let connection = app_state
         .connection_manager()
         .get_connection(id)
         .await
-        .ok_or("Connection not found")?;
+        .ok_or(Error::connection_not_found(id))?;
     let result = connection
-        .read_holding_registers(slave_id, start_address, count)
+        .read_holding_registers(
+            slave_id,
+            start_address,
+            count,
+            timeout.map(Duration::from_millis),
+        )
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.on_connection(id))?;
 
     Ok(result
         .into_iter()
@@ -180,23 +547,30 @@ pub async fn read_holding_registers(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn read_input_registers(
     app_state: State<'_, AppState>,
     id: u32,
     start_address: u16,
     count: u16,
     slave_id: u8,
-) -> Result<Vec<IndexedValue>, String> {
+    timeout: Option<u64>,
+) -> Result<Vec<IndexedValue>, CommandError> {
     info!("Reading input registers from Modbus");
     let connection = app_state
         .connection_manager()
         .get_connection(id)
         .await
-        .ok_or("Connection not found")?;
+        .ok_or(Error::connection_not_found(id))?;
     let result = connection
-        .read_input_registers(slave_id, start_address, count)
+        .read_input_registers(
+            slave_id,
+            start_address,
+            count,
+            timeout.map(Duration::from_millis),
+        )
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.on_connection(id))?;
 
     Ok(result
         .into_iter()
@@ -205,23 +579,30 @@ pub async fn read_input_registers(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn read_discrete_inputs(
     app_state: State<'_, AppState>,
     id: u32,
     start_address: u16,
     count: u16,
     slave_id: u8,
-) -> Result<Vec<IndexedValue>, String> {
+    timeout: Option<u64>,
+) -> Result<Vec<IndexedValue>, CommandError> {
     info!("Reading discrete inputs from Modbus");
     let connection = app_state
         .connection_manager()
         .get_connection(id)
         .await
-        .ok_or("Connection not found")?;
+        .ok_or(Error::connection_not_found(id))?;
     let result = connection
-        .read_discrete_inputs(slave_id, start_address, count)
+        .read_discrete_inputs(
+            slave_id,
+            start_address,
+            count,
+            timeout.map(Duration::from_millis),
+        )
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.on_connection(id))?;
 
     Ok(result
         .into_iter()
@@ -231,13 +612,15 @@ pub async fn read_discrete_inputs(
 
 // Write Data Commands
 #[tauri::command]
+#[specta::specta]
 pub async fn write_single_coil(
     app_state: State<'_, AppState>,
     id: u32,
     address: u16,
     value: bool,
     slave_id: u8,
-) -> Result<IndexedValue, String> {
+    timeout: Option<u64>,
+) -> Result<IndexedValue, CommandError> {
     info!("Writing single coil to Modbus");
     let value = Indexed::new(address, value);
 
@@ -245,23 +628,25 @@ pub async fn write_single_coil(
         .connection_manager()
         .get_connection(id)
         .await
-        .ok_or("Connection not found")?;
+        .ok_or(Error::connection_not_found(id))?;
     let result = connection
-        .write_single_coil(slave_id, value)
+        .write_single_coil(slave_id, value, timeout.map(Duration::from_millis))
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.on_connection(id))?;
 
     Ok(IndexedValue::from(result))
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn write_single_register(
     app_state: State<'_, AppState>,
     id: u32,
     address: u16,
     value: u16,
     slave_id: u8,
-) -> Result<IndexedValue, String> {
+    timeout: Option<u64>,
+) -> Result<IndexedValue, CommandError> {
     info!("Writing single register to Modbus");
     let value = Indexed::new(address, value);
 
@@ -269,62 +654,778 @@ pub async fn write_single_register(
         .connection_manager()
         .get_connection(id)
         .await
-        .ok_or("Connection not found")?;
+        .ok_or(Error::connection_not_found(id))?;
     let result = connection
-        .write_single_register(slave_id, value)
+        .write_single_register(slave_id, value, timeout.map(Duration::from_millis))
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.on_connection(id))?;
 
     Ok(IndexedValue::from(result))
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn write_multiple_coils(
     app_state: State<'_, AppState>,
     id: u32,
     start_address: u16,
     values: Vec<bool>,
     slave_id: u8,
-) -> Result<(), String> {
+    timeout: Option<u64>,
+) -> Result<(), CommandError> {
     info!("Writing multiple coils to Modbus");
     let connection = app_state
         .connection_manager()
         .get_connection(id)
         .await
-        .ok_or("Connection not found")?;
+        .ok_or(Error::connection_not_found(id))?;
     connection
-        .write_multiple_coils(slave_id, start_address, values)
+        .write_multiple_coils(
+            slave_id,
+            start_address,
+            values,
+            timeout.map(Duration::from_millis),
+        )
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.on_connection(id))?;
 
     Ok(())
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn write_multiple_registers(
     app_state: State<'_, AppState>,
     id: u32,
     address: u16,
     values: Vec<u16>,
     slave_id: u8,
-) -> Result<(), String> {
+    timeout: Option<u64>,
+) -> Result<(), CommandError> {
     info!("Writing multiple registers to Modbus");
     let connection = app_state
         .connection_manager()
         .get_connection(id)
         .await
-        .ok_or("Connection not found")?;
+        .ok_or(Error::connection_not_found(id))?;
+    connection
+        .write_multiple_registers(
+            slave_id,
+            address,
+            values,
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))?;
+
+    Ok(())
+}
+
+// Traffic Commands
+#[tauri::command]
+#[specta::specta]
+pub async fn get_traffic(
+    app_state: State<'_, AppState>,
+    id: u32,
+) -> Result<Vec<TrafficRecord>, CommandError> {
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+
+    Ok(connection.traffic().records())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_traffic(app_state: State<'_, AppState>, id: u32) -> Result<(), CommandError> {
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    connection.traffic().clear();
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_traffic(
+    app_state: State<'_, AppState>,
+    id: u32,
+    path: String,
+    format: String,
+) -> Result<(), CommandError> {
+    info!("Exporting traffic of connection {} to {}", id, path);
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let records = connection.traffic().records();
+
+    let file = std::fs::File::create(&path)?;
+    let writer = std::io::BufWriter::new(file);
+    match format.as_str() {
+        "pcap" => export::write_pcap(&records, writer),
+        "csv" => export::write_csv(&records, writer),
+        _ => return Err(Error::invalid_parameter("Invalid export format").into()),
+    }
+    .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_decode_level(
+    app_state: State<'_, AppState>,
+    id: u32,
+    decode_level: String,
+) -> Result<(), CommandError> {
+    info!(
+        "Setting decode level of connection {} to {}",
+        id, decode_level
+    );
+    let decode_level = parse_decode_level(Some(decode_level.as_str()))?;
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
     connection
-        .write_multiple_registers(slave_id, address, values)
+        .set_decode_level(decode_level)
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+// Read/Write Multiple Registers (FC 23)
+#[tauri::command]
+#[specta::specta]
+pub async fn read_write_multiple_registers(
+    app_state: State<'_, AppState>,
+    id: u32,
+    read_address: u16,
+    read_count: u16,
+    write_address: u16,
+    values: Vec<u16>,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<Vec<IndexedValue>, CommandError> {
+    info!("Reading and writing multiple registers on Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let result = connection
+        .read_write_multiple_registers(
+            slave_id,
+            read_address,
+            read_count,
+            write_address,
+            values,
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))?;
+
+    Ok(result.into_iter().map(IndexedValue::from).collect())
+}
+
+// Mask Write Register (FC 22)
+#[tauri::command]
+#[specta::specta]
+pub async fn mask_write_register(
+    app_state: State<'_, AppState>,
+    id: u32,
+    address: u16,
+    and_mask: u16,
+    or_mask: u16,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<(), CommandError> {
+    info!("Mask writing register on Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    connection
+        .mask_write_register(
+            slave_id,
+            address,
+            and_mask,
+            or_mask,
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))?;
+
+    Ok(())
+}
+
+// Serial Diagnostics Commands (FC 7, 8, 11 and 12)
+#[tauri::command]
+#[specta::specta]
+pub async fn diagnostics_loopback(
+    app_state: State<'_, AppState>,
+    id: u32,
+    data: Vec<u16>,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<Vec<u16>, CommandError> {
+    info!("Running loopback diagnostic on Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let diagnostics = connection.serial_diagnostics().ok_or_else(|| {
+        Error::invalid_parameter("Diagnostics are only available on serial connections")
+            .on_connection(id)
+    })?;
+
+    diagnostics
+        .loopback(slave_id, data, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn read_diagnostic_counters(
+    app_state: State<'_, AppState>,
+    id: u32,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<DiagnosticCounters, CommandError> {
+    info!("Reading diagnostic counters from Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let diagnostics = connection.serial_diagnostics().ok_or_else(|| {
+        Error::invalid_parameter("Diagnostics are only available on serial connections")
+            .on_connection(id)
+    })?;
+
+    diagnostics
+        .read_diagnostic_counters(slave_id, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_diagnostic_counters(
+    app_state: State<'_, AppState>,
+    id: u32,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<(), CommandError> {
+    info!("Clearing diagnostic counters on Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let diagnostics = connection.serial_diagnostics().ok_or_else(|| {
+        Error::invalid_parameter("Diagnostics are only available on serial connections")
+            .on_connection(id)
+    })?;
+
+    diagnostics
+        .clear_diagnostic_counters(slave_id, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn read_exception_status(
+    app_state: State<'_, AppState>,
+    id: u32,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<u8, CommandError> {
+    info!("Reading exception status from Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let diagnostics = connection.serial_diagnostics().ok_or_else(|| {
+        Error::invalid_parameter("Diagnostics are only available on serial connections")
+            .on_connection(id)
+    })?;
+
+    diagnostics
+        .read_exception_status(slave_id, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_comm_event_counter(
+    app_state: State<'_, AppState>,
+    id: u32,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<CommEventCounter, CommandError> {
+    info!("Reading comm event counter from Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let diagnostics = connection.serial_diagnostics().ok_or_else(|| {
+        Error::invalid_parameter("Diagnostics are only available on serial connections")
+            .on_connection(id)
+    })?;
+
+    diagnostics
+        .get_comm_event_counter(slave_id, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_comm_event_log(
+    app_state: State<'_, AppState>,
+    id: u32,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<CommEventLog, CommandError> {
+    info!("Reading comm event log from Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let diagnostics = connection.serial_diagnostics().ok_or_else(|| {
+        Error::invalid_parameter("Diagnostics are only available on serial connections")
+            .on_connection(id)
+    })?;
+
+    diagnostics
+        .get_comm_event_log(slave_id, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+// Read FIFO Queue (FC 24)
+#[tauri::command]
+#[specta::specta]
+pub async fn read_fifo_queue(
+    app_state: State<'_, AppState>,
+    id: u32,
+    pointer_address: u16,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<FifoQueue, CommandError> {
+    info!("Reading FIFO queue from Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+
+    connection
+        .read_fifo_queue(
+            slave_id,
+            pointer_address,
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+// File Record Commands (FC 20/21)
+#[tauri::command]
+#[specta::specta]
+pub async fn read_file_record(
+    app_state: State<'_, AppState>,
+    id: u32,
+    requests: Vec<FileRecordRequest>,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<Vec<FileRecord>, CommandError> {
+    info!("Reading file records from Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+
+    connection
+        .read_file_record(slave_id, requests, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn write_file_record(
+    app_state: State<'_, AppState>,
+    id: u32,
+    records: Vec<FileRecord>,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<(), CommandError> {
+    info!("Writing file records to Modbus");
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+
+    connection
+        .write_file_record(slave_id, records, timeout.map(Duration::from_millis))
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+// Raw PDU Command
+#[tauri::command]
+#[specta::specta]
+pub async fn send_raw_pdu(
+    app_state: State<'_, AppState>,
+    id: u32,
+    function_code: u8,
+    payload: Vec<u8>,
+    slave_id: u8,
+    timeout: Option<u64>,
+) -> Result<RawResponse, CommandError> {
+    info!(
+        "Sending raw PDU with function code {} on Modbus",
+        function_code
+    );
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+
+    connection
+        .send_raw_pdu(
+            slave_id,
+            function_code,
+            payload,
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+// Typed Data Commands
+#[tauri::command]
+#[specta::specta]
+pub async fn read_decoded_registers(
+    app_state: State<'_, AppState>,
+    id: u32,
+    register_type: String,
+    start_address: u16,
+    count: u16,
+    slave_id: u8,
+    data_type: DataType,
+    byte_order: Option<ByteOrder>,
+    timeout: Option<u64>,
+) -> Result<Vec<DecodedValue>, CommandError> {
+    info!("Reading {} {:?} values from Modbus", count, data_type);
+    let register_type = match RegisterType::try_from(register_type.as_str()) {
+        Ok(RegisterType::HoldingRegister) => RegisterType::HoldingRegister,
+        Ok(RegisterType::InputRegister) => RegisterType::InputRegister,
+        _ => {
+            return Err(
+                Error::invalid_parameter("Typed values can only be read from registers").into(),
+            )
+        }
+    };
+    let register_count = count
+        .checked_mul(data_type.register_count())
+        .ok_or_else(|| Error::invalid_parameter("Too many values requested"))?;
+
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let result = connection
+        .read_table(
+            slave_id,
+            register_type,
+            start_address,
+            register_count,
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))?;
+
+    let registers: Vec<u16> = result.into_iter().map(|indexed| indexed.value).collect();
+    decoding::decode_all(
+        start_address,
+        &registers,
+        data_type,
+        byte_order.unwrap_or_default(),
+    )
+    .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn write_encoded_registers(
+    app_state: State<'_, AppState>,
+    id: u32,
+    address: u16,
+    value: TypedValue,
+    slave_id: u8,
+    data_type: DataType,
+    byte_order: Option<ByteOrder>,
+    timeout: Option<u64>,
+) -> Result<(), CommandError> {
+    info!("Writing {:?} value to Modbus", data_type);
+    let registers = decoding::encode(&value, data_type, byte_order.unwrap_or_default())
+        .map_err(|e| e.on_connection(id))?;
+
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    connection
+        .write_multiple_registers(
+            slave_id,
+            address,
+            registers,
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))?;
+
+    Ok(())
+}
+
+// Polling Commands
+#[tauri::command]
+#[specta::specta]
+pub async fn start_poll_job(
+    app: AppHandle,
+    app_state: State<'_, AppState>,
+    id: u32,
+    job_id: u32,
+    register_type: String,
+    start_address: u16,
+    count: u16,
+    slave_id: u8,
+    interval_ms: u64,
+) -> Result<(), CommandError> {
+    info!("Starting poll job {} on connection {}", job_id, id);
+    if interval_ms == 0 {
+        return Err(Error::invalid_parameter("Invalid poll interval").into());
+    }
+
+    let points = planner::range_points(
+        slave_id,
+        RegisterType::try_from(register_type.as_str())?,
+        start_address,
+        count,
+    )?;
+    let job = PollJob {
+        id: job_id,
+        reads: planner::plan_reads(&points, 0)?,
+        interval: Duration::from_millis(interval_ms),
+    };
+
+    let started = app_state
+        .connection_manager()
+        .start_poll_job(app, id, job)
+        .await;
+    if !started {
+        return Err(Error::connection_not_found(id).into());
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_poll_job(
+    app_state: State<'_, AppState>,
+    id: u32,
+    job_id: u32,
+) -> Result<(), CommandError> {
+    info!("Stopping poll job {} on connection {}", job_id, id);
+    app_state
+        .connection_manager()
+        .stop_poll_job(id, job_id)
+        .await;
+
+    Ok(())
+}
+
+// Modbus Server Controller
+#[tauri::command]
+#[specta::specta]
+pub async fn start_modbus_tcp_server(
+    app_state: State<'_, AppState>,
+    id: u32,
+    host: String,
+    port: u16,
+    unit_ids: Vec<u8>,
+    size: u16,
+) -> Result<(), CommandError> {
+    info!("Starting Modbus TCP server at {}:{}", host, port);
+    let settings = ModbusServerSettings::TCP { host, port };
+    let server = ModbusServer::start(settings, unit_ids, size).await?;
+
+    app_state
+        .server_manager()
+        .add_server(id, Arc::new(server))
+        .await;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn start_modbus_rtu_server(
+    app_state: State<'_, AppState>,
+    id: u32,
+    path: String,
+    baud_rate: u32,
+    data_bits: u8,
+    parity: String,
+    stop_bits: u8,
+    unit_ids: Vec<u8>,
+    size: u16,
+) -> Result<(), CommandError> {
+    info!("Starting Modbus RTU server on {}", path);
+    let (data_bits, parity, stop_bits) = parse_serial_settings(data_bits, parity, stop_bits)?;
+    let settings = ModbusServerSettings::RTU {
+        path,
+        baud_rate,
+        data_bits,
+        parity,
+        stop_bits,
+    };
+    let server = ModbusServer::start(settings, unit_ids, size).await?;
+
+    app_state
+        .server_manager()
+        .add_server(id, Arc::new(server))
+        .await;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_modbus_server(
+    app_state: State<'_, AppState>,
+    id: u32,
+) -> Result<(), CommandError> {
+    info!("Stopping Modbus server {}", id);
+    app_state.server_manager().remove_server(id).await;
+
+    Ok(())
+}
+
+// Modbus TCP Gateway Controller
+#[tauri::command]
+#[specta::specta]
+pub async fn start_modbus_gateway(
+    app_state: State<'_, AppState>,
+    id: u32,
+    host: String,
+    port: u16,
+    connection_id: u32,
+    routes: Vec<GatewayRoute>,
+) -> Result<(), CommandError> {
+    info!(
+        "Starting Modbus TCP gateway at {}:{} for connection {}",
+        host, port, connection_id
+    );
+    let connection = app_state
+        .connection_manager()
+        .get_connection(connection_id)
+        .await
+        .ok_or(Error::connection_not_found(connection_id))?;
+    let addr = SocketAddr::new(host.parse::<IpAddr>()?, port);
+    let gateway = ModbusGateway::start(addr, connection_id, connection, routes).await?;
+
+    app_state.server_manager().add_gateway(id, gateway).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_modbus_gateway(
+    app_state: State<'_, AppState>,
+    id: u32,
+) -> Result<(), CommandError> {
+    info!("Stopping Modbus gateway {}", id);
+    app_state.server_manager().remove_gateway(id).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn read_server_registers(
+    app_state: State<'_, AppState>,
+    id: u32,
+    unit_id: u8,
+    register_type: String,
+    start_address: u16,
+    count: u16,
+) -> Result<Vec<IndexedValue>, CommandError> {
+    let register_type = RegisterType::try_from(register_type.as_str())?;
+    let server = app_state
+        .server_manager()
+        .get_server(id)
+        .await
+        .ok_or(Error::server_not_found(id))?;
+    let values = server.read(unit_id, register_type, start_address, count)?;
+
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .map(|(offset, value)| IndexedValue {
+            address: start_address + offset as u16,
+            value,
+        })
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn write_server_registers(
+    app_state: State<'_, AppState>,
+    id: u32,
+    unit_id: u8,
+    register_type: String,
+    start_address: u16,
+    values: Vec<u16>,
+) -> Result<(), CommandError> {
+    let register_type = RegisterType::try_from(register_type.as_str())?;
+    let server = app_state
+        .server_manager()
+        .get_server(id)
         .await
-        .map_err(|e| e.to_string())?;
+        .ok_or(Error::server_not_found(id))?;
+    server.write(unit_id, register_type, start_address, values)?;
 
     Ok(())
 }
 
 // IndexedValue
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, Debug, Type)]
 pub struct IndexedValue {
     pub address: u16,
     pub value: u16,
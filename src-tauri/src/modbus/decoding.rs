@@ -0,0 +1,500 @@
+use serde::{Deserialize, Serialize, Serializer};
+use specta::Type;
+
+use crate::errors::{Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum DataType {
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F32,
+    F64,
+    Bcd16,
+    Bcd32,
+    // Length in registers, two characters per register
+    Ascii { length: u16 },
+}
+
+impl DataType {
+    pub fn register_count(&self) -> u16 {
+        match self {
+            DataType::U16 | DataType::I16 | DataType::Bcd16 => 1,
+            DataType::U32 | DataType::I32 | DataType::F32 | DataType::Bcd32 => 2,
+            DataType::U64 | DataType::I64 | DataType::F64 => 4,
+            DataType::Ascii { length } => *length,
+        }
+    }
+}
+
+// Byte order of a multi-register value as it appears on the wire,
+// where "ABCD" is the big-endian order of the decoded value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub enum ByteOrder {
+    #[default]
+    ABCD,
+    CDAB,
+    BADC,
+    DCBA,
+}
+
+impl ByteOrder {
+    fn swaps_words(&self) -> bool {
+        matches!(self, ByteOrder::CDAB | ByteOrder::DCBA)
+    }
+
+    fn swaps_bytes(&self) -> bool {
+        matches!(self, ByteOrder::BADC | ByteOrder::DCBA)
+    }
+}
+
+// Largest integer a JavaScript number holds exactly, 2^53 - 1
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+// Integers beyond MAX_SAFE_INTEGER are sent as decimal strings, which the
+// frontend can parse with BigInt; such strings are accepted back for writes
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[serde(untagged)]
+pub enum TypedValue {
+    #[serde(serialize_with = "serialize_unsigned")]
+    Unsigned(u64),
+    #[serde(serialize_with = "serialize_signed")]
+    Signed(i64),
+    Float(f64),
+    Text(String),
+}
+
+fn serialize_unsigned<S: Serializer>(
+    value: &u64,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    if *value > MAX_SAFE_INTEGER {
+        serializer.collect_str(value)
+    } else {
+        serializer.serialize_u64(*value)
+    }
+}
+
+fn serialize_signed<S: Serializer>(
+    value: &i64,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    if value.unsigned_abs() > MAX_SAFE_INTEGER {
+        serializer.collect_str(value)
+    } else {
+        serializer.serialize_i64(*value)
+    }
+}
+
+impl TypedValue {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            TypedValue::Unsigned(value) => Some(*value as f64),
+            TypedValue::Signed(value) => Some(*value as f64),
+            TypedValue::Float(value) => Some(*value),
+            TypedValue::Text(_) => None,
+        }
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            TypedValue::Unsigned(value) => Some(*value as i128),
+            TypedValue::Signed(value) => Some(*value as i128),
+            TypedValue::Float(value) if value.fract() == 0.0 => Some(*value as i128),
+            // 64-bit values too large for a JavaScript number
+            TypedValue::Text(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct DecodedValue {
+    pub address: u16,
+    pub value: TypedValue,
+}
+
+// Canonical big-endian bytes of the value held in `registers`
+fn to_bytes(registers: &[u16], order: ByteOrder) -> Vec<u8> {
+    let mut words = registers.to_vec();
+    if order.swaps_words() {
+        words.reverse();
+    }
+
+    words
+        .iter()
+        .flat_map(|word| {
+            if order.swaps_bytes() {
+                word.to_le_bytes()
+            } else {
+                word.to_be_bytes()
+            }
+        })
+        .collect()
+}
+
+// Inverse of `to_bytes`
+fn from_bytes(bytes: &[u8], order: ByteOrder) -> Vec<u16> {
+    let mut words: Vec<u16> = bytes
+        .chunks(2)
+        .map(|pair| {
+            let pair = [pair[0], *pair.get(1).unwrap_or(&0)];
+            if order.swaps_bytes() {
+                u16::from_le_bytes(pair)
+            } else {
+                u16::from_be_bytes(pair)
+            }
+        })
+        .collect();
+    if order.swaps_words() {
+        words.reverse();
+    }
+
+    words
+}
+
+fn decode_bcd(bytes: &[u8]) -> Result<u64> {
+    bytes.iter().try_fold(0u64, |acc, byte| {
+        let (high, low) = (byte >> 4, byte & 0x0F);
+        if high > 9 || low > 9 {
+            return Err(Error::invalid_parameter(format!(
+                "Invalid BCD digit in 0x{:02X}",
+                byte
+            )));
+        }
+        Ok(acc * 100 + (high * 10 + low) as u64)
+    })
+}
+
+fn encode_bcd(mut value: u64, len: usize) -> Result<Vec<u8>> {
+    let mut bytes = vec![0u8; len];
+    for byte in bytes.iter_mut().rev() {
+        let low = (value % 10) as u8;
+        let high = ((value / 10) % 10) as u8;
+        *byte = (high << 4) | low;
+        value /= 100;
+    }
+    if value != 0 {
+        return Err(Error::invalid_parameter("Value out of range for BCD"));
+    }
+
+    Ok(bytes)
+}
+
+pub fn decode(registers: &[u16], data_type: DataType, order: ByteOrder) -> Result<TypedValue> {
+    if registers.len() != data_type.register_count() as usize {
+        return Err(Error::invalid_parameter(format!(
+            "Expected {} registers, got {}",
+            data_type.register_count(),
+            registers.len()
+        )));
+    }
+
+    // Strings keep their character order, only byte swapping applies
+    if let DataType::Ascii { .. } = data_type {
+        let bytes = to_bytes(
+            registers,
+            if order.swaps_bytes() {
+                ByteOrder::BADC
+            } else {
+                ByteOrder::ABCD
+            },
+        );
+        let text = bytes
+            .iter()
+            .take_while(|byte| **byte != 0)
+            .map(|byte| *byte as char)
+            .collect::<String>();
+        return Ok(TypedValue::Text(text));
+    }
+
+    // 16-bit values only take the byte swap into account
+    let bytes = to_bytes(registers, order);
+    let value = match data_type {
+        DataType::U16 => TypedValue::Unsigned(u16::from_be_bytes([bytes[0], bytes[1]]) as u64),
+        DataType::I16 => TypedValue::Signed(i16::from_be_bytes([bytes[0], bytes[1]]) as i64),
+        DataType::U32 => {
+            TypedValue::Unsigned(u32::from_be_bytes(bytes[..4].try_into().unwrap()) as u64)
+        }
+        DataType::I32 => {
+            TypedValue::Signed(i32::from_be_bytes(bytes[..4].try_into().unwrap()) as i64)
+        }
+        DataType::U64 => TypedValue::Unsigned(u64::from_be_bytes(bytes[..8].try_into().unwrap())),
+        DataType::I64 => TypedValue::Signed(i64::from_be_bytes(bytes[..8].try_into().unwrap())),
+        DataType::F32 => {
+            TypedValue::Float(f32::from_be_bytes(bytes[..4].try_into().unwrap()) as f64)
+        }
+        DataType::F64 => TypedValue::Float(f64::from_be_bytes(bytes[..8].try_into().unwrap())),
+        DataType::Bcd16 | DataType::Bcd32 => TypedValue::Unsigned(decode_bcd(&bytes)?),
+        DataType::Ascii { .. } => unreachable!(),
+    };
+
+    Ok(value)
+}
+
+pub fn decode_all(
+    start_address: u16,
+    registers: &[u16],
+    data_type: DataType,
+    order: ByteOrder,
+) -> Result<Vec<DecodedValue>> {
+    let size = data_type.register_count() as usize;
+    if size == 0 {
+        return Err(Error::invalid_parameter("Data type has no registers"));
+    }
+
+    registers
+        .chunks_exact(size)
+        .enumerate()
+        .map(|(index, chunk)| {
+            Ok(DecodedValue {
+                address: start_address + (index * size) as u16,
+                value: decode(chunk, data_type, order)?,
+            })
+        })
+        .collect()
+}
+
+pub fn encode(value: &TypedValue, data_type: DataType, order: ByteOrder) -> Result<Vec<u16>> {
+    let out_of_range =
+        || Error::invalid_parameter(format!("Value {:?} does not fit {:?}", value, data_type));
+    let integer = || value.as_i128().ok_or_else(out_of_range);
+    let float = || value.as_f64().ok_or_else(out_of_range);
+
+    let bytes = match data_type {
+        DataType::U16 => u16::try_from(integer()?)
+            .map_err(|_| out_of_range())?
+            .to_be_bytes()
+            .to_vec(),
+        DataType::I16 => i16::try_from(integer()?)
+            .map_err(|_| out_of_range())?
+            .to_be_bytes()
+            .to_vec(),
+        DataType::U32 => u32::try_from(integer()?)
+            .map_err(|_| out_of_range())?
+            .to_be_bytes()
+            .to_vec(),
+        DataType::I32 => i32::try_from(integer()?)
+            .map_err(|_| out_of_range())?
+            .to_be_bytes()
+            .to_vec(),
+        DataType::U64 => u64::try_from(integer()?)
+            .map_err(|_| out_of_range())?
+            .to_be_bytes()
+            .to_vec(),
+        DataType::I64 => i64::try_from(integer()?)
+            .map_err(|_| out_of_range())?
+            .to_be_bytes()
+            .to_vec(),
+        DataType::F32 => (float()? as f32).to_be_bytes().to_vec(),
+        DataType::F64 => float()?.to_be_bytes().to_vec(),
+        DataType::Bcd16 | DataType::Bcd32 => {
+            let value = u64::try_from(integer()?).map_err(|_| out_of_range())?;
+            encode_bcd(value, data_type.register_count() as usize * 2)?
+        }
+        DataType::Ascii { length } => {
+            let TypedValue::Text(text) = value else {
+                return Err(out_of_range());
+            };
+            if !text.is_ascii() || text.len() > length as usize * 2 {
+                return Err(out_of_range());
+            }
+            let mut bytes = text.as_bytes().to_vec();
+            bytes.resize(length as usize * 2, 0);
+            let order = if order.swaps_bytes() {
+                ByteOrder::BADC
+            } else {
+                ByteOrder::ABCD
+            };
+            return Ok(from_bytes(&bytes, order));
+        }
+    };
+
+    Ok(from_bytes(&bytes, order))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDERS: [ByteOrder; 4] = [
+        ByteOrder::ABCD,
+        ByteOrder::CDAB,
+        ByteOrder::BADC,
+        ByteOrder::DCBA,
+    ];
+
+    fn round_trip(value: TypedValue, data_type: DataType) {
+        for order in ORDERS {
+            let registers = encode(&value, data_type, order).unwrap();
+            assert_eq!(registers.len(), data_type.register_count() as usize);
+            assert_eq!(
+                decode(&registers, data_type, order).unwrap(),
+                value,
+                "{:?} in {:?}",
+                data_type,
+                order
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_every_byte_order() {
+        round_trip(TypedValue::Unsigned(0xBEEF), DataType::U16);
+        round_trip(TypedValue::Signed(-12345), DataType::I16);
+        round_trip(TypedValue::Unsigned(0xDEADBEEF), DataType::U32);
+        round_trip(TypedValue::Signed(-123456789), DataType::I32);
+        round_trip(TypedValue::Unsigned(0x0102030405060708), DataType::U64);
+        round_trip(TypedValue::Signed(-1234567890123), DataType::I64);
+        round_trip(TypedValue::Float(-2.5), DataType::F32);
+        round_trip(TypedValue::Float(std::f64::consts::PI), DataType::F64);
+        round_trip(TypedValue::Unsigned(9876), DataType::Bcd16);
+        round_trip(TypedValue::Unsigned(12345678), DataType::Bcd32);
+        round_trip(
+            TypedValue::Text("ModbusX".to_string()),
+            DataType::Ascii { length: 4 },
+        );
+    }
+
+    #[test]
+    fn decodes_known_f32_vectors() {
+        // 123.456 is 0x42F6E979
+        let vectors = [
+            (ByteOrder::ABCD, [0x42F6, 0xE979]),
+            (ByteOrder::CDAB, [0xE979, 0x42F6]),
+            (ByteOrder::BADC, [0xF642, 0x79E9]),
+            (ByteOrder::DCBA, [0x79E9, 0xF642]),
+        ];
+
+        for (order, registers) in vectors {
+            assert_eq!(
+                decode(&registers, DataType::F32, order).unwrap(),
+                TypedValue::Float(123.456f32 as f64)
+            );
+            assert_eq!(
+                encode(&TypedValue::Float(123.456), DataType::F32, order).unwrap(),
+                registers
+            );
+        }
+    }
+
+    #[test]
+    fn decodes_known_i32_vectors() {
+        // -123456789 is 0xF8A432EB
+        let vectors = [
+            (ByteOrder::ABCD, [0xF8A4, 0x32EB]),
+            (ByteOrder::CDAB, [0x32EB, 0xF8A4]),
+            (ByteOrder::BADC, [0xA4F8, 0xEB32]),
+            (ByteOrder::DCBA, [0xEB32, 0xA4F8]),
+        ];
+
+        for (order, registers) in vectors {
+            assert_eq!(
+                decode(&registers, DataType::I32, order).unwrap(),
+                TypedValue::Signed(-123456789)
+            );
+            assert_eq!(
+                encode(&TypedValue::Signed(-123456789), DataType::I32, order).unwrap(),
+                registers
+            );
+        }
+    }
+
+    #[test]
+    fn ascii_keeps_character_order_and_pads() {
+        let text = TypedValue::Text("ABC".to_string());
+        let ascii = DataType::Ascii { length: 3 };
+
+        assert_eq!(
+            encode(&text, ascii, ByteOrder::ABCD).unwrap(),
+            vec![0x4142, 0x4300, 0x0000]
+        );
+        // Word swaps do not reorder characters, byte swaps do
+        assert_eq!(
+            encode(&text, ascii, ByteOrder::CDAB).unwrap(),
+            vec![0x4142, 0x4300, 0x0000]
+        );
+        assert_eq!(
+            encode(&text, ascii, ByteOrder::DCBA).unwrap(),
+            vec![0x4241, 0x0043, 0x0000]
+        );
+        assert_eq!(
+            decode(&[0x4241, 0x0043, 0x4445], ascii, ByteOrder::BADC).unwrap(),
+            text
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_lengths() {
+        let err = decode(&[0x0001], DataType::U32, ByteOrder::ABCD).unwrap_err();
+        assert_eq!(err.kind(), "invalidParameter");
+        assert!(decode(
+            &[0x4142, 0x4344],
+            DataType::Ascii { length: 1 },
+            ByteOrder::ABCD
+        )
+        .is_err());
+        assert!(encode(
+            &TypedValue::Text("ABCDE".to_string()),
+            DataType::Ascii { length: 2 },
+            ByteOrder::ABCD
+        )
+        .is_err());
+        assert!(encode(
+            &TypedValue::Text("é".to_string()),
+            DataType::Ascii { length: 2 },
+            ByteOrder::ABCD
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        assert!(encode(
+            &TypedValue::Unsigned(0x10000),
+            DataType::U16,
+            ByteOrder::ABCD
+        )
+        .is_err());
+        assert!(encode(&TypedValue::Signed(-1), DataType::U32, ByteOrder::ABCD).is_err());
+        assert!(encode(
+            &TypedValue::Unsigned(10000),
+            DataType::Bcd16,
+            ByteOrder::ABCD
+        )
+        .is_err());
+        let err = decode(&[0x12AB], DataType::Bcd16, ByteOrder::ABCD).unwrap_err();
+        assert_eq!(err.kind(), "invalidParameter");
+    }
+
+    #[test]
+    fn large_integers_serialize_as_strings() {
+        let json = |value| serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            json(TypedValue::Unsigned(MAX_SAFE_INTEGER)),
+            "9007199254740991"
+        );
+        assert_eq!(
+            json(TypedValue::Unsigned(u64::MAX)),
+            "\"18446744073709551615\""
+        );
+        assert_eq!(json(TypedValue::Signed(-42)), "-42");
+        assert_eq!(
+            json(TypedValue::Signed(i64::MIN)),
+            "\"-9223372036854775808\""
+        );
+
+        // Written back as strings
+        let value: TypedValue = serde_json::from_str("\"18446744073709551615\"").unwrap();
+        let registers = encode(&value, DataType::U64, ByteOrder::ABCD).unwrap();
+        assert_eq!(registers, [0xFFFF; 4]);
+        assert_eq!(
+            decode(&registers, DataType::U64, ByteOrder::ABCD).unwrap(),
+            TypedValue::Unsigned(u64::MAX)
+        );
+    }
+}
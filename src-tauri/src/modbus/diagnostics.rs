@@ -0,0 +1,263 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use specta::Type;
+
+use crate::{
+    errors::Result,
+    modbus::{
+        pdu::{check_response, invalid_response},
+        ModbusConnectionTrait,
+    },
+};
+
+// Function Codes
+pub const READ_EXCEPTION_STATUS: u8 = 0x07;
+pub const DIAGNOSTICS: u8 = 0x08;
+pub const GET_COMM_EVENT_COUNTER: u8 = 0x0B;
+pub const GET_COMM_EVENT_LOG: u8 = 0x0C;
+
+// Diagnostics (FC 8) sub-functions
+pub const RETURN_QUERY_DATA: u16 = 0x00;
+pub const RETURN_DIAGNOSTIC_REGISTER: u16 = 0x02;
+pub const CLEAR_COUNTERS: u16 = 0x0A;
+pub const BUS_MESSAGE_COUNT: u16 = 0x0B;
+pub const BUS_COMMUNICATION_ERROR_COUNT: u16 = 0x0C;
+pub const BUS_EXCEPTION_ERROR_COUNT: u16 = 0x0D;
+pub const SERVER_MESSAGE_COUNT: u16 = 0x0E;
+pub const SERVER_NO_RESPONSE_COUNT: u16 = 0x0F;
+pub const SERVER_NAK_COUNT: u16 = 0x10;
+pub const SERVER_BUSY_COUNT: u16 = 0x11;
+pub const BUS_CHARACTER_OVERRUN_COUNT: u16 = 0x12;
+
+// Sub-functions of the DiagnosticCounters fields, in field order
+const COUNTERS: [u16; 9] = [
+    RETURN_DIAGNOSTIC_REGISTER,
+    BUS_MESSAGE_COUNT,
+    BUS_COMMUNICATION_ERROR_COUNT,
+    BUS_EXCEPTION_ERROR_COUNT,
+    SERVER_MESSAGE_COUNT,
+    SERVER_NO_RESPONSE_COUNT,
+    SERVER_NAK_COUNT,
+    SERVER_BUSY_COUNT,
+    BUS_CHARACTER_OVERRUN_COUNT,
+];
+
+// A status word of 0xFFFF means a previous command is still being processed
+const STATUS_BUSY: u16 = 0xFFFF;
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCounters {
+    pub diagnostic_register: u16,
+    pub bus_message_count: u16,
+    // CRC errors seen by the server
+    pub bus_communication_error_count: u16,
+    pub bus_exception_error_count: u16,
+    pub server_message_count: u16,
+    pub server_no_response_count: u16,
+    pub server_nak_count: u16,
+    pub server_busy_count: u16,
+    pub bus_character_overrun_count: u16,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CommEventCounter {
+    pub busy: bool,
+    pub event_count: u16,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CommEventLog {
+    pub busy: bool,
+    pub event_count: u16,
+    pub message_count: u16,
+    // Most recent event first
+    pub events: Vec<u8>,
+}
+
+fn word(bytes: &[u8], offset: usize) -> Result<u16> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .ok_or_else(|| invalid_response("Response too short"))
+}
+
+pub fn diagnostics_request(sub_function: u16, data: &[u16]) -> Vec<u8> {
+    let mut pdu = vec![DIAGNOSTICS];
+    pdu.extend_from_slice(&sub_function.to_be_bytes());
+    pdu.extend(data.iter().flat_map(|value| value.to_be_bytes()));
+    pdu
+}
+
+// Data words following the echoed sub-function
+pub fn parse_diagnostics(sub_function: u16, response: &[u8]) -> Result<Vec<u16>> {
+    if word(response, 1)? != sub_function {
+        return Err(invalid_response("Unexpected sub-function"));
+    }
+    let data = &response[3..];
+    if data.len() % 2 != 0 {
+        return Err(invalid_response("Odd diagnostic data length"));
+    }
+
+    Ok(data
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect())
+}
+
+// Value of a counter read with a single zero data word
+pub fn parse_counter(sub_function: u16, response: &[u8]) -> Result<u16> {
+    parse_diagnostics(sub_function, response)?
+        .first()
+        .copied()
+        .ok_or_else(|| invalid_response("Missing counter value"))
+}
+
+pub fn parse_comm_event_counter(response: &[u8]) -> Result<CommEventCounter> {
+    Ok(CommEventCounter {
+        busy: word(response, 1)? == STATUS_BUSY,
+        event_count: word(response, 3)?,
+    })
+}
+
+pub fn parse_comm_event_log(response: &[u8]) -> Result<CommEventLog> {
+    let byte_count = *response
+        .get(1)
+        .ok_or_else(|| invalid_response("Missing byte count"))? as usize;
+    let data = response
+        .get(2..2 + byte_count)
+        .filter(|data| data.len() >= 6)
+        .ok_or_else(|| invalid_response("Invalid byte count"))?;
+
+    Ok(CommEventLog {
+        busy: word(data, 0)? == STATUS_BUSY,
+        event_count: word(data, 2)?,
+        message_count: word(data, 4)?,
+        events: data[6..].to_vec(),
+    })
+}
+
+// Serial line diagnostics, only meaningful on a shared serial bus
+#[async_trait::async_trait]
+pub trait SerialDiagnostics: ModbusConnectionTrait {
+    async fn diagnostics(
+        &self,
+        slave_id: u8,
+        sub_function: u16,
+        data: &[u16],
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u16>> {
+        let request = diagnostics_request(sub_function, data);
+        let response = self.request_pdu(slave_id, request, timeout).await?;
+
+        parse_diagnostics(sub_function, &response)
+    }
+
+    async fn diagnostic_counter(
+        &self,
+        slave_id: u8,
+        sub_function: u16,
+        timeout: Option<Duration>,
+    ) -> Result<u16> {
+        let request = diagnostics_request(sub_function, &[0x0000]);
+        let response = self.request_pdu(slave_id, request, timeout).await?;
+
+        parse_counter(sub_function, &response)
+    }
+
+    // Return Query Data; the server must echo the data unchanged
+    async fn loopback(
+        &self,
+        slave_id: u8,
+        data: Vec<u16>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u16>> {
+        let echoed = self
+            .diagnostics(slave_id, RETURN_QUERY_DATA, &data, timeout)
+            .await?;
+
+        if echoed != data {
+            return Err(invalid_response("Loopback data mismatch"));
+        }
+        Ok(echoed)
+    }
+
+    // All counters are read while holding the line, so other requests
+    // cannot slip in between and the counters describe the same moment
+    async fn read_diagnostic_counters(
+        &self,
+        slave_id: u8,
+        timeout: Option<Duration>,
+    ) -> Result<DiagnosticCounters> {
+        let mut transport = self.lock_transport().await?;
+        let mut counters = [0u16; COUNTERS.len()];
+        for (counter, sub_function) in counters.iter_mut().zip(COUNTERS) {
+            let request = diagnostics_request(sub_function, &[0x0000]);
+            let response = self
+                .exchange_pdu(&mut **transport, slave_id, request, timeout)
+                .await?;
+            *counter = parse_counter(sub_function, &check_response(DIAGNOSTICS, response)?)?;
+        }
+
+        Ok(DiagnosticCounters {
+            diagnostic_register: counters[0],
+            bus_message_count: counters[1],
+            bus_communication_error_count: counters[2],
+            bus_exception_error_count: counters[3],
+            server_message_count: counters[4],
+            server_no_response_count: counters[5],
+            server_nak_count: counters[6],
+            server_busy_count: counters[7],
+            bus_character_overrun_count: counters[8],
+        })
+    }
+
+    async fn clear_diagnostic_counters(
+        &self,
+        slave_id: u8,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        self.diagnostics(slave_id, CLEAR_COUNTERS, &[0x0000], timeout)
+            .await?;
+        Ok(())
+    }
+
+    // Eight device specific exception status outputs, bit 0 first
+    async fn read_exception_status(&self, slave_id: u8, timeout: Option<Duration>) -> Result<u8> {
+        let response = self
+            .request_pdu(slave_id, vec![READ_EXCEPTION_STATUS], timeout)
+            .await?;
+
+        response
+            .get(1)
+            .copied()
+            .ok_or_else(|| invalid_response("Missing exception status"))
+    }
+
+    async fn get_comm_event_counter(
+        &self,
+        slave_id: u8,
+        timeout: Option<Duration>,
+    ) -> Result<CommEventCounter> {
+        let response = self
+            .request_pdu(slave_id, vec![GET_COMM_EVENT_COUNTER], timeout)
+            .await?;
+
+        parse_comm_event_counter(&response)
+    }
+
+    async fn get_comm_event_log(
+        &self,
+        slave_id: u8,
+        timeout: Option<Duration>,
+    ) -> Result<CommEventLog> {
+        let response = self
+            .request_pdu(slave_id, vec![GET_COMM_EVENT_LOG], timeout)
+            .await?;
+
+        parse_comm_event_log(&response)
+    }
+}
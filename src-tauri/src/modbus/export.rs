@@ -0,0 +1,291 @@
+use std::io::Write;
+
+use crate::{
+    errors::Result,
+    modbus::{
+        frame::{hex, mbap_frame, rtu_frame},
+        traffic::{Direction, TrafficRecord},
+    },
+};
+
+const PCAP_MAGIC: u32 = 0xA1B2_C3D4;
+const LINKTYPE_ETHERNET: u32 = 1;
+const CLIENT_ADDR: [u8; 4] = [10, 0, 0, 1];
+const SERVER_ADDR: [u8; 4] = [10, 0, 0, 2];
+const CLIENT_PORT: u16 = 49152;
+const SERVER_PORT: u16 = 502;
+
+fn checksum(chunks: &[&[u8]]) -> u16 {
+    let mut sum = 0u32;
+    for chunk in chunks {
+        for pair in chunk.chunks(2) {
+            sum += u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32;
+        }
+    }
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+// Ethernet + IPv4 + TCP headers around a Modbus TCP ADU
+fn tcp_packet(from_client: bool, seq: u32, ack: u32, payload: &[u8]) -> Vec<u8> {
+    let (src_addr, dst_addr, src_port, dst_port) = if from_client {
+        (CLIENT_ADDR, SERVER_ADDR, CLIENT_PORT, SERVER_PORT)
+    } else {
+        (SERVER_ADDR, CLIENT_ADDR, SERVER_PORT, CLIENT_PORT)
+    };
+
+    let mut tcp = Vec::with_capacity(20 + payload.len());
+    tcp.extend_from_slice(&src_port.to_be_bytes());
+    tcp.extend_from_slice(&dst_port.to_be_bytes());
+    tcp.extend_from_slice(&seq.to_be_bytes());
+    tcp.extend_from_slice(&ack.to_be_bytes());
+    tcp.extend_from_slice(&[0x50, 0x18]); // 20 byte header, PSH + ACK
+    tcp.extend_from_slice(&0xFFFF_u16.to_be_bytes());
+    tcp.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+    tcp.extend_from_slice(payload);
+    let pseudo_header = [
+        &src_addr[..],
+        &dst_addr[..],
+        &[0x00, 0x06],
+        &(tcp.len() as u16).to_be_bytes(),
+    ]
+    .concat();
+    let tcp_checksum = checksum(&[&pseudo_header, &tcp]);
+    tcp[16..18].copy_from_slice(&tcp_checksum.to_be_bytes());
+
+    let mut ip = Vec::with_capacity(20);
+    ip.extend_from_slice(&[0x45, 0x00]);
+    ip.extend_from_slice(&(20 + tcp.len() as u16).to_be_bytes());
+    ip.extend_from_slice(&[0x00, 0x00, 0x40, 0x00, 0x40, 0x06, 0x00, 0x00]);
+    ip.extend_from_slice(&src_addr);
+    ip.extend_from_slice(&dst_addr);
+    let ip_checksum = checksum(&[&ip]);
+    ip[10..12].copy_from_slice(&ip_checksum.to_be_bytes());
+
+    let mut packet = Vec::with_capacity(14 + ip.len() + tcp.len());
+    let (src_mac, dst_mac) = if from_client {
+        ([0x02, 0, 0, 0, 0, 0x01], [0x02, 0, 0, 0, 0, 0x02])
+    } else {
+        ([0x02, 0, 0, 0, 0, 0x02], [0x02, 0, 0, 0, 0, 0x01])
+    };
+    packet.extend_from_slice(&dst_mac);
+    packet.extend_from_slice(&src_mac);
+    packet.extend_from_slice(&[0x08, 0x00]);
+    packet.extend(ip);
+    packet.extend(tcp);
+    packet
+}
+
+// Writes records as Modbus TCP traffic over a synthetic TCP stream.
+// Responses without data (timeouts, closed links) are skipped.
+pub fn write_pcap(records: &[TrafficRecord], mut writer: impl Write) -> Result<()> {
+    writer.write_all(&PCAP_MAGIC.to_le_bytes())?;
+    writer.write_all(&2u16.to_le_bytes())?;
+    writer.write_all(&4u16.to_le_bytes())?;
+    writer.write_all(&0i32.to_le_bytes())?;
+    writer.write_all(&0u32.to_le_bytes())?;
+    writer.write_all(&65535u32.to_le_bytes())?;
+    writer.write_all(&LINKTYPE_ETHERNET.to_le_bytes())?;
+
+    let (mut client_seq, mut server_seq) = (1u32, 1u32);
+    for record in records.iter().filter(|record| !record.raw.is_empty()) {
+        let adu = mbap_frame(record.transaction_id as u16, record.unit_id, &record.raw);
+        let from_client = matches!(record.direction, Direction::Request);
+        let packet = if from_client {
+            let packet = tcp_packet(true, client_seq, server_seq, &adu);
+            client_seq = client_seq.wrapping_add(adu.len() as u32);
+            packet
+        } else {
+            let packet = tcp_packet(false, server_seq, client_seq, &adu);
+            server_seq = server_seq.wrapping_add(adu.len() as u32);
+            packet
+        };
+
+        writer.write_all(&((record.timestamp / 1000) as u32).to_le_bytes())?;
+        writer.write_all(&((record.timestamp % 1000 * 1000) as u32).to_le_bytes())?;
+        writer.write_all(&(packet.len() as u32).to_le_bytes())?;
+        writer.write_all(&(packet.len() as u32).to_le_bytes())?;
+        writer.write_all(&packet)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+// Writes records as RTU frames, one CSV row per frame. The frame's CRC is
+// computed for the export; the crc column holds the status of the CRC
+// received on the wire, left empty when there was none.
+pub fn write_csv(records: &[TrafficRecord], mut writer: impl Write) -> Result<()> {
+    writeln!(
+        writer,
+        "timestamp,transaction_id,direction,unit_id,function_code,frame,crc,latency_ms,error"
+    )?;
+
+    for record in records {
+        let frame = if record.raw.is_empty() {
+            String::new()
+        } else {
+            hex(&rtu_frame(record.unit_id, &record.raw))
+        };
+        let direction = match record.direction {
+            Direction::Request => "request",
+            Direction::Response => "response",
+        };
+        let crc = match record.crc_valid {
+            Some(true) => "valid",
+            Some(false) => "invalid",
+            None => "",
+        };
+        let latency = record
+            .latency_ms
+            .map(|latency| format!("{:.3}", latency))
+            .unwrap_or_default();
+        let error = record
+            .error
+            .as_deref()
+            .unwrap_or_default()
+            .replace('"', "\"\"");
+
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},\"{}\"",
+            record.timestamp,
+            record.transaction_id,
+            direction,
+            record.unit_id,
+            record.function_code,
+            frame,
+            crc,
+            latency,
+            error
+        )?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn records() -> Vec<TrafficRecord> {
+        let record = |direction, raw: Vec<u8>, crc_valid, error: Option<&str>| TrafficRecord {
+            connection_id: 1,
+            transaction_id: 7,
+            timestamp: 1_700_000_000_123,
+            direction,
+            unit_id: 1,
+            function_code: raw.first().copied().unwrap_or(0x03),
+            raw,
+            reconstructed: false,
+            crc_valid,
+            latency_ms: None,
+            error: error.map(str::to_string),
+        };
+
+        vec![
+            record(
+                Direction::Request,
+                vec![0x03, 0x00, 0x00, 0x00, 0x01],
+                None,
+                None,
+            ),
+            record(
+                Direction::Response,
+                vec![0x03, 0x02, 0x12, 0x34],
+                Some(true),
+                None,
+            ),
+            record(
+                Direction::Request,
+                vec![0x03, 0x00, 0x10, 0x00, 0x01],
+                None,
+                None,
+            ),
+            record(Direction::Response, vec![], None, Some("response timeout")),
+            record(
+                Direction::Request,
+                vec![0x03, 0x00, 0x20, 0x00, 0x01],
+                None,
+                None,
+            ),
+            record(
+                Direction::Response,
+                vec![],
+                Some(false),
+                Some("CRC check failed"),
+            ),
+        ]
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("modbusx-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn pcap_round_trip() {
+        let path = temp_path("traffic.pcap");
+        write_pcap(&records(), File::create(&path).unwrap()).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(bytes[..4], PCAP_MAGIC.to_le_bytes());
+        assert_eq!(bytes[20..24], LINKTYPE_ETHERNET.to_le_bytes());
+
+        let mut packets = Vec::new();
+        let mut offset = 24;
+        while offset < bytes.len() {
+            let header = &bytes[offset..offset + 16];
+            let seconds = u32::from_le_bytes(header[0..4].try_into().unwrap());
+            let length = u32::from_le_bytes(header[8..12].try_into().unwrap()) as usize;
+            assert_eq!(seconds, 1_700_000_000);
+            packets.push(&bytes[offset + 16..offset + 16 + length]);
+            offset += 16 + length;
+        }
+        assert_eq!(packets.len(), 4);
+
+        let request = packets[0];
+        assert_eq!(request[12..14], [0x08, 0x00]);
+        assert_eq!(checksum(&[&request[14..34]]), 0);
+        assert_eq!(request[36..38], SERVER_PORT.to_be_bytes());
+        assert_eq!(
+            &request[54..],
+            &mbap_frame(7, 1, &[0x03, 0x00, 0x00, 0x00, 0x01])[..]
+        );
+
+        let response = packets[1];
+        assert_eq!(response[34..36], SERVER_PORT.to_be_bytes());
+        assert_eq!(
+            &response[54..],
+            &mbap_frame(7, 1, &[0x03, 0x02, 0x12, 0x34])[..]
+        );
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let path = temp_path("traffic.csv");
+        write_csv(&records(), File::create(&path).unwrap()).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let rows: Vec<Vec<&str>> = content
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').collect())
+            .collect();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0][2], "request");
+        assert_eq!(rows[0][5], "01 03 00 00 00 01 84 0A");
+        assert_eq!(rows[0][6], "");
+        assert_eq!(rows[1][6], "valid");
+        assert_eq!(rows[3][5], "");
+        assert_eq!(rows[3][6], "");
+        assert_eq!(rows[3][8], "\"response timeout\"");
+        assert_eq!(rows[5][6], "invalid");
+        assert_eq!(rows[5][8], "\"CRC check failed\"");
+    }
+}
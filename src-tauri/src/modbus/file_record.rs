@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::{
+    errors::{Error, Result},
+    modbus::pdu::{self, invalid_response},
+};
+
+// The only reference type defined by the specification
+const REFERENCE_TYPE: u8 = 0x06;
+const MAX_RECORD_NUMBER: u16 = 0x270F;
+// Largest request or response data length that fits in a PDU
+const MAX_DATA_LENGTH: usize = 0xF5;
+
+#[derive(Clone, Copy, Debug, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRecordRequest {
+    pub file_number: u16,
+    pub record_number: u16,
+    // Length in registers
+    pub length: u16,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRecord {
+    pub file_number: u16,
+    pub record_number: u16,
+    pub values: Vec<u16>,
+}
+
+fn check_reference(file_number: u16, record_number: u16) -> Result<()> {
+    if file_number == 0 {
+        return Err(Error::invalid_parameter("File number must start at 1"));
+    }
+    if record_number > MAX_RECORD_NUMBER {
+        return Err(Error::invalid_parameter(format!(
+            "Record number {} exceeds {}",
+            record_number, MAX_RECORD_NUMBER
+        )));
+    }
+    Ok(())
+}
+
+fn check_data_length(length: usize) -> Result<()> {
+    if length == 0 || length > MAX_DATA_LENGTH {
+        return Err(Error::invalid_parameter(format!(
+            "File record data length {} must be between 1 and {} bytes",
+            length, MAX_DATA_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+fn sub_request_header(file_number: u16, record_number: u16, length: u16) -> [u8; 7] {
+    let mut header = [REFERENCE_TYPE, 0, 0, 0, 0, 0, 0];
+    header[1..3].copy_from_slice(&file_number.to_be_bytes());
+    header[3..5].copy_from_slice(&record_number.to_be_bytes());
+    header[5..7].copy_from_slice(&length.to_be_bytes());
+    header
+}
+
+// Read File Record (FC 20)
+pub fn read_file_record(requests: &[FileRecordRequest]) -> Result<Vec<u8>> {
+    for request in requests {
+        check_reference(request.file_number, request.record_number)?;
+    }
+    check_data_length(requests.len() * 7)?;
+    // Each sub-response carries a length and reference type byte
+    check_data_length(
+        requests
+            .iter()
+            .map(|request| 2 + request.length as usize * 2)
+            .sum(),
+    )?;
+
+    let mut pdu = vec![pdu::READ_FILE_RECORD, (requests.len() * 7) as u8];
+    for request in requests {
+        pdu.extend_from_slice(&sub_request_header(
+            request.file_number,
+            request.record_number,
+            request.length,
+        ));
+    }
+    Ok(pdu)
+}
+
+pub fn parse_read_file_record(
+    requests: &[FileRecordRequest],
+    response: &[u8],
+) -> Result<Vec<FileRecord>> {
+    let length = *response
+        .get(1)
+        .ok_or_else(|| invalid_response("Missing data length"))? as usize;
+    let data = response
+        .get(2..2 + length)
+        .ok_or_else(|| invalid_response("Invalid data length"))?;
+
+    let mut records = Vec::with_capacity(requests.len());
+    let mut offset = 0;
+    for request in requests {
+        let sub_length = *data
+            .get(offset)
+            .ok_or_else(|| invalid_response("Missing file sub-response"))?
+            as usize;
+        if sub_length != 1 + request.length as usize * 2 {
+            return Err(invalid_response("Unexpected file record length"));
+        }
+        let sub_response = data
+            .get(offset + 1..offset + 1 + sub_length)
+            .ok_or_else(|| invalid_response("Truncated file sub-response"))?;
+        if sub_response[0] != REFERENCE_TYPE {
+            return Err(invalid_response("Unexpected reference type"));
+        }
+
+        records.push(FileRecord {
+            file_number: request.file_number,
+            record_number: request.record_number,
+            values: sub_response[1..]
+                .chunks(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect(),
+        });
+        offset += 1 + sub_length;
+    }
+
+    Ok(records)
+}
+
+// Write File Record (FC 21); the response echoes the request
+pub fn write_file_record(records: &[FileRecord]) -> Result<Vec<u8>> {
+    for record in records {
+        check_reference(record.file_number, record.record_number)?;
+        if record.values.is_empty() {
+            return Err(Error::invalid_parameter("File record has no values"));
+        }
+    }
+    let length = records
+        .iter()
+        .map(|record| 7 + record.values.len() * 2)
+        .sum();
+    check_data_length(length)?;
+
+    let mut pdu = vec![pdu::WRITE_FILE_RECORD, length as u8];
+    for record in records {
+        pdu.extend_from_slice(&sub_request_header(
+            record.file_number,
+            record.record_number,
+            record.values.len() as u16,
+        ));
+        pdu.extend(record.values.iter().flat_map(|value| value.to_be_bytes()));
+    }
+    Ok(pdu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Examples of the application protocol spec
+    const READ_REQUESTS: [FileRecordRequest; 2] = [
+        FileRecordRequest {
+            file_number: 4,
+            record_number: 1,
+            length: 2,
+        },
+        FileRecordRequest {
+            file_number: 3,
+            record_number: 9,
+            length: 2,
+        },
+    ];
+    const READ_RESPONSE: [u8; 14] = [
+        0x14, 0x0C, 0x05, 0x06, 0x0D, 0xFE, 0x00, 0x20, 0x05, 0x06, 0x33, 0xCD, 0x00, 0x40,
+    ];
+
+    #[test]
+    fn builds_read_request() {
+        assert_eq!(
+            read_file_record(&READ_REQUESTS).unwrap(),
+            vec![
+                0x14, 0x0E, 0x06, 0x00, 0x04, 0x00, 0x01, 0x00, 0x02, 0x06, 0x00, 0x03, 0x00, 0x09,
+                0x00, 0x02,
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_multi_record_response() {
+        let records = parse_read_file_record(&READ_REQUESTS, &READ_RESPONSE).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].file_number, records[0].record_number), (4, 1));
+        assert_eq!(records[0].values, vec![0x0DFE, 0x0020]);
+        assert_eq!((records[1].file_number, records[1].record_number), (3, 9));
+        assert_eq!(records[1].values, vec![0x33CD, 0x0040]);
+    }
+
+    #[test]
+    fn rejects_truncated_response() {
+        for length in [1, 2, 8, READ_RESPONSE.len() - 1] {
+            assert!(
+                parse_read_file_record(&READ_REQUESTS, &READ_RESPONSE[..length]).is_err(),
+                "{} bytes",
+                length
+            );
+        }
+
+        // A consistent data length that stops after the first record
+        let mut short = READ_RESPONSE[..8].to_vec();
+        short[1] = 0x06;
+        assert!(parse_read_file_record(&READ_REQUESTS, &short).is_err());
+    }
+
+    #[test]
+    fn rejects_unexpected_sub_response() {
+        let mut wrong_length = READ_RESPONSE;
+        wrong_length[2] = 0x03;
+        assert!(parse_read_file_record(&READ_REQUESTS, &wrong_length).is_err());
+
+        let mut wrong_reference = READ_RESPONSE;
+        wrong_reference[9] = 0x07;
+        assert!(parse_read_file_record(&READ_REQUESTS, &wrong_reference).is_err());
+    }
+
+    #[test]
+    fn builds_write_request() {
+        let record = FileRecord {
+            file_number: 4,
+            record_number: 7,
+            values: vec![0x06AF, 0x04BE, 0x100D],
+        };
+
+        assert_eq!(
+            write_file_record(&[record]).unwrap(),
+            vec![
+                0x15, 0x0D, 0x06, 0x00, 0x04, 0x00, 0x07, 0x00, 0x03, 0x06, 0xAF, 0x04, 0xBE, 0x10,
+                0x0D,
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_requests() {
+        let request = |file_number, record_number, length| FileRecordRequest {
+            file_number,
+            record_number,
+            length,
+        };
+
+        assert!(read_file_record(&[request(0, 1, 1)]).is_err());
+        assert!(read_file_record(&[request(1, MAX_RECORD_NUMBER + 1, 1)]).is_err());
+        assert!(read_file_record(&[]).is_err());
+        // 2 + 122 * 2 bytes of sub-response exceed the PDU
+        assert!(read_file_record(&[request(1, 0, 122)]).is_err());
+        assert!(read_file_record(&[request(1, 0, 121)]).is_ok());
+
+        let empty = FileRecord {
+            file_number: 1,
+            record_number: 0,
+            values: Vec::new(),
+        };
+        assert!(write_file_record(&[empty]).is_err());
+    }
+}
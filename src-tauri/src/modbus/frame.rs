@@ -0,0 +1,212 @@
+// Application data unit framing for the serial and TCP transports
+
+use crate::{errors::Result, modbus::pdu::invalid_response};
+
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFF_u16;
+    for byte in data {
+        crc ^= *byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+// Unit ID, the longest PDU and CRC
+pub const MAX_RTU_FRAME_LENGTH: usize = 256;
+
+// Unit ID, PDU and CRC (low byte first)
+pub fn rtu_frame(unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(pdu.len() + 3);
+    frame.push(unit_id);
+    frame.extend_from_slice(pdu);
+    frame.extend_from_slice(&crc16(&frame).to_le_bytes());
+    frame
+}
+
+// Checks the trailing CRC of a complete RTU frame
+pub fn rtu_crc_valid(frame: &[u8]) -> bool {
+    if frame.len() < 4 {
+        return false;
+    }
+    let (body, crc) = frame.split_at(frame.len() - 2);
+    crc16(body).to_le_bytes() == [crc[0], crc[1]]
+}
+
+// MBAP header followed by the PDU
+pub fn mbap_frame(transaction_id: u16, unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(pdu.len() + 7);
+    frame.extend_from_slice(&transaction_id.to_be_bytes());
+    frame.extend_from_slice(&[0x00, 0x00]);
+    frame.extend_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+    frame.push(unit_id);
+    frame.extend_from_slice(pdu);
+    frame
+}
+
+// Transaction ID, unit ID and PDU of a complete MBAP frame
+pub fn parse_mbap_frame(frame: &[u8]) -> Result<(u16, u8, Vec<u8>)> {
+    if frame.len() < 8 {
+        return Err(invalid_response("MBAP frame too short"));
+    }
+    if frame[2..4] != [0x00, 0x00] {
+        return Err(invalid_response("Invalid MBAP protocol ID"));
+    }
+    if u16::from_be_bytes([frame[4], frame[5]]) as usize != frame.len() - 6 {
+        return Err(invalid_response("MBAP length does not match the frame"));
+    }
+
+    Ok((
+        u16::from_be_bytes([frame[0], frame[1]]),
+        frame[6],
+        frame[7..].to_vec(),
+    ))
+}
+
+// Two's complement of the byte sum, as used by Modbus ASCII
+pub fn lrc(data: &[u8]) -> u8 {
+    data.iter()
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+        .wrapping_neg()
+}
+
+// Colon, unit ID, PDU and LRC as uppercase hex, then CR LF
+pub fn ascii_frame(unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(pdu.len() + 2);
+    body.push(unit_id);
+    body.extend_from_slice(pdu);
+    body.push(lrc(&body));
+
+    let mut frame = Vec::with_capacity(body.len() * 2 + 3);
+    frame.push(b':');
+    for byte in body {
+        frame.extend_from_slice(format!("{:02X}", byte).as_bytes());
+    }
+    frame.extend_from_slice(b"\r\n");
+    frame
+}
+
+// Unit ID and PDU of an ASCII frame, with or without the colon and CR LF.
+// The eighth bit is ignored so 7-bit characters read with 8 data bits decode.
+pub fn parse_ascii_frame(frame: &[u8]) -> Result<(u8, Vec<u8>)> {
+    let text: Vec<u8> = frame.iter().map(|byte| byte & 0x7F).collect();
+    let text = text.strip_prefix(b":").unwrap_or(&text);
+    let text = text.strip_suffix(b"\r\n").unwrap_or(text);
+    if text.len() < 6 || text.len() % 2 != 0 {
+        return Err(invalid_response("Invalid ASCII frame length"));
+    }
+
+    let body = text
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| invalid_response("Invalid hex character"))
+        })
+        .collect::<Result<Vec<u8>>>()?;
+
+    let (data, checksum) = body.split_at(body.len() - 1);
+    if lrc(data) != checksum[0] {
+        return Err(invalid_response("LRC check failed"));
+    }
+    Ok((data[0], data[1..].to_vec()))
+}
+
+// Space separated uppercase hex, for logs and exports
+pub fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Read Holding Registers from unit 17, the example of the serial line spec
+    const READ_REQUEST: &[u8] = b":1103006B00037E\r\n";
+
+    #[test]
+    fn lrc_matches_known_values() {
+        assert_eq!(lrc(&[0x11, 0x03, 0x00, 0x6B, 0x00, 0x03]), 0x7E);
+        assert_eq!(lrc(&[0x01, 0x03, 0x00, 0x00, 0x00, 0x01]), 0xFB);
+        assert_eq!(lrc(&[]), 0x00);
+        // The sum wraps around
+        assert_eq!(lrc(&[0xFF, 0xFF, 0x02]), 0x00);
+    }
+
+    #[test]
+    fn ascii_frame_matches_known_frame() {
+        assert_eq!(
+            ascii_frame(0x11, &[0x03, 0x00, 0x6B, 0x00, 0x03]),
+            READ_REQUEST
+        );
+    }
+
+    #[test]
+    fn parses_known_ascii_frames() {
+        let expected = (0x11, vec![0x03, 0x00, 0x6B, 0x00, 0x03]);
+        assert_eq!(parse_ascii_frame(READ_REQUEST).unwrap(), expected);
+        assert_eq!(parse_ascii_frame(b"1103006B00037E").unwrap(), expected);
+        assert_eq!(parse_ascii_frame(b":1103006b00037e\r\n").unwrap(), expected);
+
+        // Parity in the eighth bit
+        let with_parity: Vec<u8> = READ_REQUEST.iter().map(|byte| byte | 0x80).collect();
+        assert_eq!(parse_ascii_frame(&with_parity).unwrap(), expected);
+    }
+
+    #[test]
+    fn ascii_frame_round_trips() {
+        let pdu = [0x10, 0x00, 0x01, 0x00, 0x02, 0x04, 0x00, 0x0A, 0x01, 0x02];
+        assert_eq!(
+            parse_ascii_frame(&ascii_frame(0xF7, &pdu)).unwrap(),
+            (0xF7, pdu.to_vec())
+        );
+    }
+
+    #[test]
+    fn rejects_bad_lrc() {
+        assert!(parse_ascii_frame(b":1103006B00037F\r\n").is_err());
+    }
+
+    #[test]
+    fn rejects_odd_or_invalid_hex() {
+        assert!(parse_ascii_frame(b":1103006B00037\r\n").is_err());
+        assert!(parse_ascii_frame(b":1103006G00037E\r\n").is_err());
+        assert!(parse_ascii_frame(b":1103\r\n").is_err());
+    }
+
+    #[test]
+    fn parses_mbap_frames() {
+        let frame = mbap_frame(0x1234, 0x11, &[0x03, 0x02, 0x00, 0x2A]);
+        assert_eq!(
+            parse_mbap_frame(&frame).unwrap(),
+            (0x1234, 0x11, vec![0x03, 0x02, 0x00, 0x2A])
+        );
+
+        let mut protocol = frame.clone();
+        protocol[2] = 0x01;
+        assert!(parse_mbap_frame(&protocol).is_err());
+
+        let mut length = frame.clone();
+        length[5] = 0x04;
+        assert!(parse_mbap_frame(&length).is_err());
+        assert!(parse_mbap_frame(&frame[..frame.len() - 1]).is_err());
+        assert!(parse_mbap_frame(&frame[..7]).is_err());
+    }
+
+    #[test]
+    fn rtu_frame_matches_known_crc() {
+        let frame = rtu_frame(0x01, &[0x03, 0x00, 0x00, 0x00, 0x01]);
+        assert_eq!(frame, [0x01, 0x03, 0x00, 0x00, 0x00, 0x01, 0x84, 0x0A]);
+        assert!(rtu_crc_valid(&frame));
+    }
+}
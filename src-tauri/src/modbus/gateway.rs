@@ -0,0 +1,346 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+
+use log::{info, warn};
+use rodbus::ExceptionCode;
+use serde::Deserialize;
+use specta::Type;
+use tauri::async_runtime::JoinHandle;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinSet,
+};
+
+use crate::{
+    errors::{Error, Result},
+    modbus::{connection_manager::ConnectionId, frame::mbap_frame, pdu, ModbusConnectionTrait},
+};
+
+// Largest MBAP length field: unit ID plus a 253 byte PDU
+const MAX_MBAP_LENGTH: usize = 254;
+
+// Pause after a failed accept, e.g. when out of file descriptors
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayRoute {
+    pub tcp_unit_id: u8,
+    pub slave_id: u8,
+}
+
+// Maps TCP unit IDs to slaves behind the connection. Requests share the
+// connection's transport, so they are serialized with pollers and UI reads.
+struct Router {
+    connection: Arc<dyn ModbusConnectionTrait>,
+    // Without routes every unit ID is passed through unchanged
+    routes: HashMap<u8, u8>,
+}
+
+impl Router {
+    // Broadcasts get no response
+    async fn forward(&self, unit_id: u8, request: Vec<u8>) -> Option<Vec<u8>> {
+        let function_code = request[0];
+        let slave_id = if self.routes.is_empty() {
+            unit_id
+        } else {
+            match self.routes.get(&unit_id) {
+                Some(slave_id) => *slave_id,
+                None => {
+                    return Some(pdu::exception_response(
+                        function_code,
+                        ExceptionCode::GatewayPathUnavailable,
+                    ))
+                }
+            }
+        };
+
+        // Unit 0 only means broadcast on a serial line
+        if slave_id == 0 && self.connection.serial_diagnostics().is_some() {
+            if let Err(e) = self.connection.broadcast_pdu(request).await {
+                warn!("Gateway broadcast failed: {}", e);
+            }
+            return None;
+        }
+
+        match self.connection.forward_pdu(slave_id, request, None).await {
+            Ok(response) => Some(response),
+            Err(e) => {
+                warn!("Gateway request to slave {} failed: {}", slave_id, e);
+                Some(pdu::exception_response(
+                    function_code,
+                    ExceptionCode::GatewayTargetDeviceFailedToRespond,
+                ))
+            }
+        }
+    }
+}
+
+// Answers Modbus TCP requests from one client until it disconnects or
+// sends a malformed frame
+async fn serve(mut stream: TcpStream, router: Arc<Router>) {
+    loop {
+        let mut header = [0u8; 7];
+        if stream.read_exact(&mut header).await.is_err() {
+            return;
+        }
+        let protocol_id = u16::from_be_bytes([header[2], header[3]]);
+        if protocol_id != 0 {
+            warn!("Gateway client sent protocol ID {}", protocol_id);
+            return;
+        }
+        let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+        if !(2..=MAX_MBAP_LENGTH).contains(&length) {
+            warn!("Gateway client sent an invalid MBAP length {}", length);
+            return;
+        }
+        let mut request = vec![0u8; length - 1];
+        if stream.read_exact(&mut request).await.is_err() {
+            return;
+        }
+
+        let transaction_id = u16::from_be_bytes([header[0], header[1]]);
+        let unit_id = header[6];
+        let Some(response) = router.forward(unit_id, request).await else {
+            continue;
+        };
+        if stream
+            .write_all(&mbap_frame(transaction_id, unit_id, &response))
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+// Modbus TCP server that forwards requests to an open connection,
+// stopped when dropped
+pub struct ModbusGateway {
+    // Connection the requests are forwarded to; the gateway keeps it alive,
+    // so it is stopped when that connection is closed
+    connection_id: ConnectionId,
+    handle: JoinHandle<()>,
+}
+
+impl ModbusGateway {
+    pub async fn start(
+        addr: SocketAddr,
+        connection_id: ConnectionId,
+        connection: Arc<dyn ModbusConnectionTrait>,
+        routes: Vec<GatewayRoute>,
+    ) -> Result<Self> {
+        if connection.transport().is_none() {
+            return Err(Error::invalid_parameter(
+                "The gateway cannot forward requests over TLS connections",
+            ));
+        }
+        let listener = TcpListener::bind(addr).await?;
+
+        Ok(Self::listen(listener, connection_id, connection, routes))
+    }
+
+    pub fn connection_id(&self) -> ConnectionId {
+        self.connection_id
+    }
+
+    fn listen(
+        listener: TcpListener,
+        connection_id: ConnectionId,
+        connection: Arc<dyn ModbusConnectionTrait>,
+        routes: Vec<GatewayRoute>,
+    ) -> Self {
+        let router = Arc::new(Router {
+            connection,
+            routes: routes
+                .into_iter()
+                .map(|route| (route.tcp_unit_id, route.slave_id))
+                .collect(),
+        });
+
+        let handle = tauri::async_runtime::spawn(async move {
+            // Sessions are aborted along with the accept loop
+            let mut sessions = JoinSet::new();
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        info!("Gateway client connected from {}", peer);
+                        sessions.spawn(serve(stream, router.clone()));
+                    }
+                    Err(e) => {
+                        warn!("Gateway failed to accept a client: {}", e);
+                        tokio::time::sleep(ACCEPT_BACKOFF).await;
+                    }
+                }
+                while sessions.try_join_next().is_some() {}
+            }
+        });
+
+        Self {
+            connection_id,
+            handle,
+        }
+    }
+}
+
+impl Drop for ModbusGateway {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        errors::ModbusError,
+        modbus::{
+            connection::{HasChannel, ModbusConnectionSettings},
+            diagnostics::SerialDiagnostics,
+            traffic::{Direction, TrafficLog},
+            transport::{shared, PduTransport, SharedTransport},
+        },
+    };
+    use rodbus::{client::Channel, RequestError};
+
+    // Slave 5 echoes every request, no other slave answers
+    struct EchoTransport;
+
+    #[async_trait::async_trait]
+    impl PduTransport for EchoTransport {
+        async fn transact(
+            &mut self,
+            unit_id: u8,
+            request: &[u8],
+            _timeout: Duration,
+        ) -> Result<Vec<u8>> {
+            if unit_id == 5 {
+                Ok(request.to_vec())
+            } else {
+                Err(Error::ModbusError(ModbusError::RequestError(
+                    RequestError::ResponseTimeout,
+                )))
+            }
+        }
+
+        async fn broadcast(&mut self, _request: &[u8], _timeout: Duration) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct EchoConnection {
+        transport: SharedTransport,
+        traffic: TrafficLog,
+        // Whether the connection is on a serial line, where unit 0 broadcasts
+        serial: bool,
+    }
+
+    impl HasChannel for EchoConnection {
+        fn channel(&self) -> Option<&Channel> {
+            None
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ModbusConnectionTrait for EchoConnection {
+        async fn establish_connection(&mut self, _: ModbusConnectionSettings) -> Result<()> {
+            Ok(())
+        }
+
+        fn timeout(&self) -> Duration {
+            Duration::from_millis(100)
+        }
+
+        fn traffic(&self) -> &TrafficLog {
+            &self.traffic
+        }
+
+        fn serial_diagnostics(&self) -> Option<&dyn SerialDiagnostics> {
+            self.serial.then_some(self as &dyn SerialDiagnostics)
+        }
+
+        fn transport(&self) -> Option<&SharedTransport> {
+            Some(&self.transport)
+        }
+    }
+
+    impl SerialDiagnostics for EchoConnection {}
+
+    fn echo_connection(serial: bool) -> Arc<EchoConnection> {
+        Arc::new(EchoConnection {
+            transport: shared(EchoTransport),
+            traffic: TrafficLog::new(1, None),
+            serial,
+        })
+    }
+
+    async fn request(stream: &mut TcpStream, unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+        stream
+            .write_all(&mbap_frame(9, unit_id, pdu))
+            .await
+            .unwrap();
+
+        let mut header = [0u8; 7];
+        stream.read_exact(&mut header).await.unwrap();
+        assert_eq!(&header[..4], &[0, 9, 0, 0]);
+        assert_eq!(header[6], unit_id);
+        let mut response = vec![0u8; u16::from_be_bytes([header[4], header[5]]) as usize - 1];
+        stream.read_exact(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn gateway_routes_requests_and_reports_failures() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connection = echo_connection(false);
+        let routes = vec![
+            GatewayRoute {
+                tcp_unit_id: 1,
+                slave_id: 5,
+            },
+            GatewayRoute {
+                tcp_unit_id: 2,
+                slave_id: 6,
+            },
+        ];
+        let _gateway = ModbusGateway::listen(listener, 1, connection, routes);
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        let read = [0x03, 0x00, 0x10, 0x00, 0x02];
+        assert_eq!(request(&mut stream, 1, &read).await, read);
+        // Unrouted unit ID
+        assert_eq!(request(&mut stream, 3, &read).await, [0x83, 0x0A]);
+        // Routed to a slave that does not answer
+        assert_eq!(request(&mut stream, 2, &read).await, [0x83, 0x0B]);
+    }
+
+    #[tokio::test]
+    async fn unit_zero_is_broadcast_only_on_serial_lines() {
+        let write = [0x06, 0x00, 0x01, 0x00, 0x2A];
+
+        let serial = echo_connection(true);
+        let router = Router {
+            connection: serial.clone(),
+            routes: HashMap::new(),
+        };
+        assert_eq!(router.forward(0, write.to_vec()).await, None);
+        let records = serial.traffic.records();
+        assert_eq!(records.len(), 1);
+        assert!(matches!(records[0].direction, Direction::Request));
+        assert_eq!(records[0].unit_id, 0);
+        assert_eq!(records[0].raw, write);
+
+        // Elsewhere unit 0 is an ordinary unit ID
+        let tcp = echo_connection(false);
+        let router = Router {
+            connection: tcp.clone(),
+            routes: HashMap::new(),
+        };
+        assert_eq!(
+            router.forward(0, write.to_vec()).await,
+            Some(vec![0x86, 0x0B])
+        );
+        assert_eq!(tcp.traffic.records().len(), 2);
+    }
+}
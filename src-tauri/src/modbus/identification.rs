@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::{
+    errors::Result,
+    modbus::pdu::{self, invalid_response},
+};
+
+pub const MEI_READ_DEVICE_IDENTIFICATION: u8 = 0x0E;
+
+// Read Device ID code of an FC 43 / MEI 14 request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceIdCategory {
+    Basic,
+    Regular,
+    Extended,
+    Individual,
+}
+
+impl DeviceIdCategory {
+    fn code(&self) -> u8 {
+        match self {
+            DeviceIdCategory::Basic => 0x01,
+            DeviceIdCategory::Regular => 0x02,
+            DeviceIdCategory::Extended => 0x03,
+            DeviceIdCategory::Individual => 0x04,
+        }
+    }
+}
+
+// Standard names of objects 0x00 - 0x06; the rest are regular or vendor specific
+fn object_name(id: u8) -> Option<&'static str> {
+    match id {
+        0x00 => Some("VendorName"),
+        0x01 => Some("ProductCode"),
+        0x02 => Some("MajorMinorRevision"),
+        0x03 => Some("VendorUrl"),
+        0x04 => Some("ProductName"),
+        0x05 => Some("ModelName"),
+        0x06 => Some("UserApplicationName"),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceObject {
+    pub id: u8,
+    pub name: Option<&'static str>,
+    pub value: String,
+    pub raw: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceIdentification {
+    pub conformity_level: u8,
+    pub objects: Vec<DeviceObject>,
+}
+
+// One response of a possibly multi-part identification read
+pub struct DeviceIdentificationPart {
+    pub conformity_level: u8,
+    pub next_object_id: Option<u8>,
+    pub objects: Vec<DeviceObject>,
+}
+
+pub fn read_device_identification(category: DeviceIdCategory, object_id: u8) -> Vec<u8> {
+    vec![
+        pdu::ENCAPSULATED_INTERFACE_TRANSPORT,
+        MEI_READ_DEVICE_IDENTIFICATION,
+        category.code(),
+        object_id,
+    ]
+}
+
+pub fn parse_device_identification(response: &[u8]) -> Result<DeviceIdentificationPart> {
+    let header = response
+        .get(..7)
+        .ok_or_else(|| invalid_response("Device identification response too short"))?;
+    if header[1] != MEI_READ_DEVICE_IDENTIFICATION {
+        return Err(invalid_response("Unexpected MEI type"));
+    }
+
+    let mut objects = Vec::with_capacity(header[6] as usize);
+    let mut offset = 7;
+    for _ in 0..header[6] {
+        let (id, length) = match response.get(offset..offset + 2) {
+            Some(object) => (object[0], object[1] as usize),
+            None => return Err(invalid_response("Truncated device object")),
+        };
+        let raw = response
+            .get(offset + 2..offset + 2 + length)
+            .ok_or_else(|| invalid_response("Truncated device object"))?
+            .to_vec();
+
+        objects.push(DeviceObject {
+            id,
+            name: object_name(id),
+            value: String::from_utf8_lossy(&raw).into_owned(),
+            raw,
+        });
+        offset += 2 + length;
+    }
+
+    Ok(DeviceIdentificationPart {
+        conformity_level: header[3],
+        next_object_id: (header[4] == 0xFF).then_some(header[5]),
+        objects,
+    })
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerIdReport {
+    pub server_id: u8,
+    pub running: bool,
+    // Device specific data following the run indicator
+    pub additional_data: Vec<u8>,
+}
+
+pub fn parse_report_server_id(response: &[u8]) -> Result<ServerIdReport> {
+    let byte_count = *response
+        .get(1)
+        .ok_or_else(|| invalid_response("Missing byte count"))? as usize;
+    let data = response
+        .get(2..2 + byte_count)
+        .filter(|data| data.len() >= 2)
+        .ok_or_else(|| invalid_response("Invalid byte count"))?;
+
+    Ok(ServerIdReport {
+        server_id: data[0],
+        running: data[1] == 0xFF,
+        additional_data: data[2..].to_vec(),
+    })
+}
@@ -1,7 +1,22 @@
 pub mod connection;
 pub mod connection_manager;
 pub mod controller;
+pub mod decoding;
+pub mod diagnostics;
+pub mod export;
+pub mod file_record;
+pub mod frame;
+pub mod gateway;
+pub mod identification;
+pub mod pdu;
+pub mod planner;
+pub mod poller;
 pub mod server;
+pub mod server_manager;
+pub mod sniffer;
+pub mod status;
+pub mod traffic;
+pub mod transport;
 
 pub use connection::ModbusConnectionTrait;
 pub use controller::*;
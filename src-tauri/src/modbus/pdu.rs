@@ -0,0 +1,373 @@
+use rodbus::{AddressRange, ExceptionCode, Indexed};
+use serde::Serialize;
+use specta::Type;
+
+use crate::errors::{Error, ModbusError, Result};
+
+// Function Codes
+pub const READ_COILS: u8 = 0x01;
+pub const READ_DISCRETE_INPUTS: u8 = 0x02;
+pub const READ_HOLDING_REGISTERS: u8 = 0x03;
+pub const READ_INPUT_REGISTERS: u8 = 0x04;
+pub const WRITE_SINGLE_COIL: u8 = 0x05;
+pub const WRITE_SINGLE_REGISTER: u8 = 0x06;
+pub const WRITE_MULTIPLE_COILS: u8 = 0x0F;
+pub const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+pub const REPORT_SERVER_ID: u8 = 0x11;
+pub const READ_FILE_RECORD: u8 = 0x14;
+pub const WRITE_FILE_RECORD: u8 = 0x15;
+pub const MASK_WRITE_REGISTER: u8 = 0x16;
+pub const READ_WRITE_MULTIPLE_REGISTERS: u8 = 0x17;
+pub const READ_FIFO_QUEUE: u8 = 0x18;
+pub const ENCAPSULATED_INTERFACE_TRANSPORT: u8 = 0x2B;
+
+pub const EXCEPTION_FLAG: u8 = 0x80;
+
+// Function code and data; the RTU frame adds address and CRC up to 256 bytes
+pub const MAX_PDU_LENGTH: usize = 253;
+
+// Servers answer larger queues with an illegal data value exception
+pub const MAX_FIFO_COUNT: u16 = 31;
+
+pub fn exception_code(code: ExceptionCode) -> u8 {
+    match code {
+        ExceptionCode::IllegalFunction => 0x01,
+        ExceptionCode::IllegalDataAddress => 0x02,
+        ExceptionCode::IllegalDataValue => 0x03,
+        ExceptionCode::ServerDeviceFailure => 0x04,
+        ExceptionCode::Acknowledge => 0x05,
+        ExceptionCode::ServerDeviceBusy => 0x06,
+        ExceptionCode::MemoryParityError => 0x08,
+        ExceptionCode::GatewayPathUnavailable => 0x0A,
+        ExceptionCode::GatewayTargetDeviceFailedToRespond => 0x0B,
+        ExceptionCode::Unknown(code) => code,
+    }
+}
+
+pub fn exception_from_code(code: u8) -> ExceptionCode {
+    match code {
+        0x01 => ExceptionCode::IllegalFunction,
+        0x02 => ExceptionCode::IllegalDataAddress,
+        0x03 => ExceptionCode::IllegalDataValue,
+        0x04 => ExceptionCode::ServerDeviceFailure,
+        0x05 => ExceptionCode::Acknowledge,
+        0x06 => ExceptionCode::ServerDeviceBusy,
+        0x08 => ExceptionCode::MemoryParityError,
+        0x0A => ExceptionCode::GatewayPathUnavailable,
+        0x0B => ExceptionCode::GatewayTargetDeviceFailedToRespond,
+        code => ExceptionCode::Unknown(code),
+    }
+}
+
+pub fn invalid_response(message: &str) -> Error {
+    Error::ModbusError(ModbusError::InvalidResponse(message.to_string()))
+}
+
+// Turns exception responses into errors and checks the echoed function code
+pub fn check_response(function_code: u8, response: Vec<u8>) -> Result<Vec<u8>> {
+    match response.first() {
+        None => Err(invalid_response("Empty response")),
+        Some(code) if *code == function_code | EXCEPTION_FLAG => {
+            let exception = response.get(1).copied().unwrap_or_default();
+            Err(Error::ModbusError(ModbusError::Exception(
+                exception_from_code(exception),
+            )))
+        }
+        Some(code) if *code != function_code => Err(invalid_response("Unexpected function code")),
+        Some(_) => Ok(response),
+    }
+}
+
+// Response to an arbitrary request, with exceptions kept as data
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RawResponse {
+    #[serde(rename_all = "camelCase")]
+    Response { function_code: u8, data: Vec<u8> },
+    #[serde(rename_all = "camelCase")]
+    Exception {
+        function_code: u8,
+        exception_code: u8,
+        description: String,
+    },
+}
+
+pub fn raw_response(function_code: u8, response: Vec<u8>) -> Result<RawResponse> {
+    match check_response(function_code, response) {
+        Ok(response) => Ok(RawResponse::Response {
+            function_code,
+            data: response[1..].to_vec(),
+        }),
+        Err(Error::ModbusError(ModbusError::Exception(code))) => Ok(RawResponse::Exception {
+            function_code,
+            exception_code: exception_code(code),
+            description: code.to_string(),
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn exception_response(function_code: u8, code: ExceptionCode) -> Vec<u8> {
+    vec![function_code | EXCEPTION_FLAG, exception_code(code)]
+}
+
+fn pack_bits(bits: impl ExactSizeIterator<Item = bool>) -> Vec<u8> {
+    let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+    for (index, bit) in bits.enumerate() {
+        if bit {
+            bytes[index / 8] |= 1 << (index % 8);
+        }
+    }
+    bytes
+}
+
+fn words(values: impl Iterator<Item = u16>) -> Vec<u8> {
+    values.flat_map(u16::to_be_bytes).collect()
+}
+
+// Requests
+pub fn read_request(function_code: u8, range: AddressRange) -> Vec<u8> {
+    let mut pdu = vec![function_code];
+    pdu.extend_from_slice(&range.start.to_be_bytes());
+    pdu.extend_from_slice(&range.count.to_be_bytes());
+    pdu
+}
+
+pub fn write_single_coil(value: Indexed<bool>) -> Vec<u8> {
+    let mut pdu = vec![WRITE_SINGLE_COIL];
+    pdu.extend_from_slice(&value.index.to_be_bytes());
+    pdu.extend_from_slice(&if value.value {
+        [0xFF, 0x00]
+    } else {
+        [0x00, 0x00]
+    });
+    pdu
+}
+
+pub fn write_single_register(value: Indexed<u16>) -> Vec<u8> {
+    let mut pdu = vec![WRITE_SINGLE_REGISTER];
+    pdu.extend_from_slice(&value.index.to_be_bytes());
+    pdu.extend_from_slice(&value.value.to_be_bytes());
+    pdu
+}
+
+pub fn write_multiple_coils(start: u16, values: &[bool]) -> Vec<u8> {
+    let data = pack_bits(values.iter().copied());
+    let mut pdu = read_request(
+        WRITE_MULTIPLE_COILS,
+        AddressRange {
+            start,
+            count: values.len() as u16,
+        },
+    );
+    pdu.push(data.len() as u8);
+    pdu.extend(data);
+    pdu
+}
+
+pub fn write_multiple_registers(start: u16, values: &[u16]) -> Vec<u8> {
+    let data = words(values.iter().copied());
+    let mut pdu = read_request(
+        WRITE_MULTIPLE_REGISTERS,
+        AddressRange {
+            start,
+            count: values.len() as u16,
+        },
+    );
+    pdu.push(data.len() as u8);
+    pdu.extend(data);
+    pdu
+}
+
+// Responses
+pub fn bits_response(function_code: u8, values: &[Indexed<bool>]) -> Vec<u8> {
+    let data = pack_bits(values.iter().map(|indexed| indexed.value));
+    let mut pdu = vec![function_code, data.len() as u8];
+    pdu.extend(data);
+    pdu
+}
+
+pub fn registers_response(function_code: u8, values: &[Indexed<u16>]) -> Vec<u8> {
+    let data = words(values.iter().map(|indexed| indexed.value));
+    let mut pdu = vec![function_code, data.len() as u8];
+    pdu.extend(data);
+    pdu
+}
+
+// Bits from a byte-count prefixed response, e.g. FC 1 and 2
+pub fn bits_from_response(range: AddressRange, response: &[u8]) -> Result<Vec<Indexed<bool>>> {
+    check_range(range)?;
+    let byte_count = *response
+        .get(1)
+        .ok_or_else(|| invalid_response("Missing byte count"))? as usize;
+    let data = response
+        .get(2..2 + byte_count)
+        .filter(|data| data.len() == (range.count as usize).div_ceil(8))
+        .ok_or_else(|| invalid_response("Invalid byte count"))?;
+
+    Ok((0..range.count)
+        .map(|offset| {
+            let bit = data[offset as usize / 8] & (1 << (offset % 8)) != 0;
+            Indexed::new(range.start + offset, bit)
+        })
+        .collect())
+}
+
+// Addresses of the range must not run past the end of the address space
+fn check_range(range: AddressRange) -> Result<()> {
+    if range.start as u32 + range.count as u32 > u16::MAX as u32 + 1 {
+        return Err(Error::invalid_parameter("Address range exceeds 65535"));
+    }
+    Ok(())
+}
+
+// Checked before a request is built, as rodbus does for its own requests
+pub fn check_request_range(range: AddressRange, max: u16) -> Result<()> {
+    if range.count == 0 || range.count > max {
+        return Err(Error::invalid_parameter(format!(
+            "Count {} must be between 1 and {}",
+            range.count, max
+        )));
+    }
+    check_range(range)
+}
+
+// Registers from a byte-count prefixed response, e.g. FC 3, 4 and 23
+pub fn registers_from_response(range: AddressRange, response: &[u8]) -> Result<Vec<Indexed<u16>>> {
+    check_range(range)?;
+    let byte_count = *response
+        .get(1)
+        .ok_or_else(|| invalid_response("Missing byte count"))? as usize;
+    let data = response
+        .get(2..2 + byte_count)
+        .filter(|data| data.len() == range.count as usize * 2)
+        .ok_or_else(|| invalid_response("Invalid byte count"))?;
+
+    Ok(data
+        .chunks(2)
+        .zip(0..)
+        .map(|(pair, offset)| {
+            Indexed::new(range.start + offset, u16::from_be_bytes([pair[0], pair[1]]))
+        })
+        .collect())
+}
+
+// Read/Write Multiple Registers (FC 23)
+pub fn read_write_multiple_registers(
+    read_range: AddressRange,
+    write_start: u16,
+    values: &[u16],
+) -> Vec<u8> {
+    let data = words(values.iter().copied());
+    let mut pdu = read_request(READ_WRITE_MULTIPLE_REGISTERS, read_range);
+    pdu.extend_from_slice(&write_start.to_be_bytes());
+    pdu.extend_from_slice(&(values.len() as u16).to_be_bytes());
+    pdu.push(data.len() as u8);
+    pdu.extend(data);
+    pdu
+}
+
+// Mask Write Register (FC 22)
+pub fn mask_write_register(address: u16, and_mask: u16, or_mask: u16) -> Vec<u8> {
+    let mut pdu = vec![MASK_WRITE_REGISTER];
+    pdu.extend_from_slice(&address.to_be_bytes());
+    pdu.extend_from_slice(&and_mask.to_be_bytes());
+    pdu.extend_from_slice(&or_mask.to_be_bytes());
+    pdu
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct FifoQueue {
+    pub count: u16,
+    pub values: Vec<u16>,
+}
+
+// Read FIFO Queue (FC 24)
+pub fn read_fifo_queue(pointer_address: u16) -> Vec<u8> {
+    let mut pdu = vec![READ_FIFO_QUEUE];
+    pdu.extend_from_slice(&pointer_address.to_be_bytes());
+    pdu
+}
+
+pub fn parse_fifo_queue(response: &[u8]) -> Result<FifoQueue> {
+    let header = response
+        .get(..5)
+        .ok_or_else(|| invalid_response("FIFO response too short"))?;
+    let byte_count = u16::from_be_bytes([header[1], header[2]]) as usize;
+    let count = u16::from_be_bytes([header[3], header[4]]);
+
+    if count > MAX_FIFO_COUNT {
+        return Err(invalid_response("FIFO count exceeds 31"));
+    }
+    if byte_count != 2 + count as usize * 2 {
+        return Err(invalid_response("Invalid byte count"));
+    }
+    let data = response
+        .get(5..3 + byte_count)
+        .ok_or_else(|| invalid_response("Truncated FIFO response"))?;
+
+    Ok(FifoQueue {
+        count,
+        values: data
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fifo_response(count: u16, values: &[u16]) -> Vec<u8> {
+        let mut response = vec![READ_FIFO_QUEUE];
+        response.extend_from_slice(&(2 + values.len() as u16 * 2).to_be_bytes());
+        response.extend_from_slice(&count.to_be_bytes());
+        response.extend(values.iter().flat_map(|value| value.to_be_bytes()));
+        response
+    }
+
+    #[test]
+    fn builds_fifo_request() {
+        assert_eq!(read_fifo_queue(0x04DE), vec![0x18, 0x04, 0xDE]);
+    }
+
+    #[test]
+    fn parses_fifo_queue() {
+        // Example of the application protocol spec
+        let response = [0x18, 0x00, 0x06, 0x00, 0x02, 0x01, 0xB8, 0x12, 0x84];
+        let queue = parse_fifo_queue(&response).unwrap();
+
+        assert_eq!(queue.count, 2);
+        assert_eq!(queue.values, vec![0x01B8, 0x1284]);
+
+        let empty = parse_fifo_queue(&fifo_response(0, &[])).unwrap();
+        assert_eq!((empty.count, empty.values.len()), (0, 0));
+
+        let full = parse_fifo_queue(&fifo_response(31, &[0xABCD; 31])).unwrap();
+        assert_eq!(full.values, vec![0xABCD; 31]);
+    }
+
+    #[test]
+    fn rejects_fifo_count_above_31() {
+        assert!(parse_fifo_queue(&fifo_response(32, &[0; 32])).is_err());
+        assert!(parse_fifo_queue(&fifo_response(0xFFFF, &[])).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_fifo_response() {
+        let response = fifo_response(2, &[0x01B8, 0x1284]);
+        for length in 0..response.len() {
+            assert!(
+                parse_fifo_queue(&response[..length]).is_err(),
+                "{} bytes",
+                length
+            );
+        }
+
+        // Byte count that disagrees with the FIFO count
+        let mut mismatched = response;
+        mismatched[4] = 0x03;
+        assert!(parse_fifo_queue(&mismatched).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    errors::{Error, Result},
+    modbus::connection::{RegisterType, MAX_READ_BITS, MAX_READ_REGISTERS},
+};
+
+pub const DEFAULT_MAX_GAP: u16 = 8;
+
+// A span of addresses that has to be read, e.g. one multi-register tag
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadPoint {
+    pub slave_id: u8,
+    pub register_type: RegisterType,
+    pub address: u16,
+    pub count: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlannedRead {
+    pub slave_id: u8,
+    pub register_type: RegisterType,
+    pub start_address: u16,
+    pub count: u16,
+}
+
+impl PlannedRead {
+    fn end(&self) -> u32 {
+        self.start_address as u32 + self.count as u32
+    }
+
+    // Whether the span of `point` is fully covered by this read
+    pub fn contains(&self, point: &ReadPoint) -> bool {
+        self.slave_id == point.slave_id
+            && self.register_type == point.register_type
+            && point.address >= self.start_address
+            && point.address as u32 + point.count as u32 <= self.end()
+    }
+}
+
+pub fn max_count(register_type: RegisterType) -> u16 {
+    match register_type {
+        RegisterType::Coil | RegisterType::DiscreteInput => MAX_READ_BITS,
+        RegisterType::HoldingRegister | RegisterType::InputRegister => MAX_READ_REGISTERS,
+    }
+}
+
+// Points covering a contiguous range, split at the request limit. A range
+// running past the last address is an error.
+pub fn range_points(
+    slave_id: u8,
+    register_type: RegisterType,
+    address: u16,
+    count: u16,
+) -> Result<Vec<ReadPoint>> {
+    let limit = max_count(register_type) as u32;
+    let end = address as u32 + count as u32;
+    if end > 0x10000 {
+        return Err(Error::invalid_parameter(format!(
+            "{} items at address {} run past the last address",
+            count, address
+        )));
+    }
+
+    Ok((address as u32..end)
+        .step_by(limit as usize)
+        .map(|start| ReadPoint {
+            slave_id,
+            register_type,
+            address: start as u16,
+            count: (end - start).min(limit) as u16,
+        })
+        .collect())
+}
+
+// Merges scattered points into the fewest protocol-compliant reads, bridging
+// holes of up to `max_gap` unused addresses between neighbouring points.
+// A point too large for a single request is an error.
+pub fn plan_reads(points: &[ReadPoint], max_gap: u16) -> Result<Vec<PlannedRead>> {
+    let mut tables: BTreeMap<(u8, u8), Vec<&ReadPoint>> = BTreeMap::new();
+    for point in points.iter().filter(|point| point.count > 0) {
+        tables
+            .entry((point.slave_id, table_order(point.register_type)))
+            .or_default()
+            .push(point);
+    }
+
+    let mut reads = Vec::new();
+    for mut table in tables.into_values() {
+        table.sort_by_key(|point| (point.address, point.count));
+        let limit = max_count(table[0].register_type) as u32;
+
+        let mut current: Option<PlannedRead> = None;
+        for point in table {
+            if point.count as u32 > limit {
+                return Err(Error::invalid_parameter(format!(
+                    "{} items at address {} exceed the limit of {} per request",
+                    point.count, point.address, limit
+                )));
+            }
+            let point_end = point.address as u32 + point.count as u32;
+
+            if let Some(read) = current.as_mut() {
+                let gap = (point.address as u32).saturating_sub(read.end());
+                let merged_end = read.end().max(point_end);
+                if gap <= max_gap as u32 && merged_end - read.start_address as u32 <= limit {
+                    read.count = (merged_end - read.start_address as u32) as u16;
+                    continue;
+                }
+                reads.push(*read);
+            }
+
+            current = Some(PlannedRead {
+                slave_id: point.slave_id,
+                register_type: point.register_type,
+                start_address: point.address,
+                count: point.count,
+            });
+        }
+        reads.extend(current);
+    }
+
+    Ok(reads)
+}
+
+fn table_order(register_type: RegisterType) -> u8 {
+    match register_type {
+        RegisterType::Coil => 0,
+        RegisterType::DiscreteInput => 1,
+        RegisterType::HoldingRegister => 2,
+        RegisterType::InputRegister => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(slave_id: u8, address: u16, count: u16) -> ReadPoint {
+        ReadPoint {
+            slave_id,
+            register_type: RegisterType::HoldingRegister,
+            address,
+            count,
+        }
+    }
+
+    #[test]
+    fn merges_points_within_gap() {
+        let points = [holding(1, 4, 2), holding(1, 0, 1), holding(1, 8, 1)];
+        let reads = plan_reads(&points, 3).unwrap();
+
+        assert_eq!(
+            reads,
+            vec![PlannedRead {
+                slave_id: 1,
+                register_type: RegisterType::HoldingRegister,
+                start_address: 0,
+                count: 9,
+            }]
+        );
+        assert!(points.iter().all(|point| reads[0].contains(point)));
+    }
+
+    #[test]
+    fn splits_points_beyond_gap() {
+        let reads = plan_reads(&[holding(1, 0, 2), holding(1, 10, 2)], 3).unwrap();
+
+        assert_eq!(reads.len(), 2);
+        assert_eq!((reads[0].start_address, reads[0].count), (0, 2));
+        assert_eq!((reads[1].start_address, reads[1].count), (10, 2));
+    }
+
+    #[test]
+    fn honors_protocol_limits() {
+        let reads = plan_reads(&[holding(1, 0, 2), holding(1, 124, 2)], 200).unwrap();
+        assert_eq!(reads.len(), 2);
+        assert!(reads.iter().all(|read| read.count <= MAX_READ_REGISTERS));
+
+        let coils: Vec<ReadPoint> = (0..2500)
+            .step_by(500)
+            .map(|address| ReadPoint {
+                slave_id: 1,
+                register_type: RegisterType::Coil,
+                address,
+                count: 1,
+            })
+            .collect();
+        let reads = plan_reads(&coils, 1000).unwrap();
+        assert_eq!(reads.len(), 2);
+        assert_eq!((reads[0].start_address, reads[0].count), (0, 1501));
+        assert_eq!((reads[1].start_address, reads[1].count), (2000, 1));
+    }
+
+    #[test]
+    fn separates_units_and_tables() {
+        let points = [
+            holding(1, 0, 1),
+            holding(2, 1, 1),
+            ReadPoint {
+                slave_id: 1,
+                register_type: RegisterType::InputRegister,
+                address: 1,
+                count: 1,
+            },
+        ];
+        let reads = plan_reads(&points, 10).unwrap();
+
+        assert_eq!(reads.len(), 3);
+        assert!(points
+            .iter()
+            .all(|point| reads.iter().any(|read| read.contains(point))));
+    }
+
+    #[test]
+    fn overlapping_points_share_a_read() {
+        let reads = plan_reads(&[holding(1, 0, 4), holding(1, 2, 2), holding(1, 3, 4)], 0).unwrap();
+
+        assert_eq!(reads.len(), 1);
+        assert_eq!((reads[0].start_address, reads[0].count), (0, 7));
+    }
+
+    #[test]
+    fn rejects_oversize_points() {
+        assert!(plan_reads(&[holding(1, 0, MAX_READ_REGISTERS + 1)], 0).is_err());
+    }
+
+    #[test]
+    fn range_points_fit_in_single_reads() {
+        let points = range_points(1, RegisterType::HoldingRegister, 10, 300).unwrap();
+        let counts: Vec<(u16, u16)> = points
+            .iter()
+            .map(|point| (point.address, point.count))
+            .collect();
+        assert_eq!(counts, [(10, 125), (135, 125), (260, 50)]);
+
+        let reads = plan_reads(&points, DEFAULT_MAX_GAP).unwrap();
+        assert_eq!(reads.len(), 3);
+    }
+
+    #[test]
+    fn range_points_stop_at_the_last_address() {
+        let points = range_points(1, RegisterType::HoldingRegister, 0xFF00, 0x100).unwrap();
+        let last = points.last().unwrap();
+        assert_eq!(last.address as u32 + last.count as u32, 0x10000);
+
+        assert!(range_points(1, RegisterType::HoldingRegister, 0xFF00, 0x101).is_err());
+        assert!(range_points(1, RegisterType::Coil, 0xFFF0, 100).is_err());
+    }
+}
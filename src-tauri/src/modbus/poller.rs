@@ -0,0 +1,116 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use log::error;
+use serde::Serialize;
+use specta::Type;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::time::MissedTickBehavior;
+
+use crate::{
+    errors::Result,
+    modbus::{
+        connection_manager::ConnectionId, controller::IndexedValue, planner::PlannedRead,
+        ModbusConnectionTrait,
+    },
+};
+
+pub const POLL_EVENT: &str = "modbus-poll";
+
+pub type PollJobId = u32;
+
+#[derive(Clone)]
+pub struct PollJob {
+    pub id: PollJobId,
+    // Planned when the job starts, see planner::plan_reads
+    pub reads: Vec<PlannedRead>,
+    pub interval: Duration,
+}
+
+#[derive(Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PollEvent {
+    pub connection_id: ConnectionId,
+    pub job_id: PollJobId,
+    pub timestamp: u64,
+    pub values: Option<Vec<IndexedValue>>,
+    pub error: Option<String>,
+}
+
+// Poll task for a single job, aborted when the handle is dropped
+pub struct Poller {
+    handle: JoinHandle<()>,
+}
+
+impl Poller {
+    pub fn spawn(
+        app: AppHandle,
+        connection_id: ConnectionId,
+        connection: Arc<dyn ModbusConnectionTrait>,
+        job: PollJob,
+    ) -> Self {
+        let handle = tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(job.interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                let (values, error) = match read_all(connection.as_ref(), &job.reads).await {
+                    Ok(values) => (Some(values), None),
+                    Err(e) => (None, Some(e.to_string())),
+                };
+
+                let event = PollEvent {
+                    connection_id,
+                    job_id: job.id,
+                    timestamp: timestamp_millis(),
+                    values,
+                    error,
+                };
+
+                if let Err(e) = app.emit(POLL_EVENT, event) {
+                    error!("Failed to emit poll event: {}", e);
+                }
+            }
+        });
+
+        Self { handle }
+    }
+}
+
+async fn read_all(
+    connection: &dyn ModbusConnectionTrait,
+    reads: &[PlannedRead],
+) -> Result<Vec<IndexedValue>> {
+    let mut values = Vec::new();
+    for read in reads {
+        let result = connection
+            .read_table(
+                read.slave_id,
+                read.register_type,
+                read.start_address,
+                read.count,
+                None,
+            )
+            .await?;
+        values.extend(result.into_iter().map(IndexedValue::from));
+    }
+
+    Ok(values)
+}
+
+impl Drop for Poller {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+pub fn timestamp_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -1 +1,318 @@
+use std::{collections::HashMap, net::SocketAddr};
 
+use crate::errors::{Error, ModbusError, Result};
+use crate::modbus::connection::RegisterType;
+use rodbus::server::*;
+use rodbus::*;
+
+pub enum ModbusServerSettings {
+    RTU {
+        path: String,
+        baud_rate: u32,
+        data_bits: DataBits,
+        parity: Parity,
+        stop_bits: StopBits,
+    },
+    TCP {
+        host: String,
+        port: u16,
+    },
+}
+
+// In-memory register map served for a single unit ID
+pub struct SimulatorHandler {
+    coils: Vec<bool>,
+    discrete_inputs: Vec<bool>,
+    holding_registers: Vec<u16>,
+    input_registers: Vec<u16>,
+}
+
+impl SimulatorHandler {
+    pub fn new(size: u16) -> Self {
+        let size = size as usize;
+        Self {
+            coils: vec![false; size],
+            discrete_inputs: vec![false; size],
+            holding_registers: vec![0; size],
+            input_registers: vec![0; size],
+        }
+    }
+
+    pub fn read(&self, register_type: RegisterType, start: u16, count: u16) -> Result<Vec<u16>> {
+        let range = start as usize..start as usize + count as usize;
+        let values = match register_type {
+            RegisterType::Coil => self
+                .coils
+                .get(range)
+                .map(|values| values.iter().map(|v| *v as u16).collect()),
+            RegisterType::DiscreteInput => self
+                .discrete_inputs
+                .get(range)
+                .map(|values| values.iter().map(|v| *v as u16).collect()),
+            RegisterType::HoldingRegister => self.holding_registers.get(range).map(<[u16]>::to_vec),
+            RegisterType::InputRegister => self.input_registers.get(range).map(<[u16]>::to_vec),
+        };
+
+        values.ok_or(Error::ModbusError(ModbusError::Exception(
+            ExceptionCode::IllegalDataAddress,
+        )))
+    }
+
+    pub fn write(&mut self, register_type: RegisterType, start: u16, values: &[u16]) -> Result<()> {
+        let range = start as usize..start as usize + values.len();
+        let written = match register_type {
+            RegisterType::Coil => Self::write_bits(&mut self.coils, range, values),
+            RegisterType::DiscreteInput => {
+                Self::write_bits(&mut self.discrete_inputs, range, values)
+            }
+            RegisterType::HoldingRegister => {
+                Self::write_words(&mut self.holding_registers, range, values)
+            }
+            RegisterType::InputRegister => {
+                Self::write_words(&mut self.input_registers, range, values)
+            }
+        };
+
+        if written {
+            Ok(())
+        } else {
+            Err(Error::ModbusError(ModbusError::Exception(
+                ExceptionCode::IllegalDataAddress,
+            )))
+        }
+    }
+
+    fn write_bits(table: &mut [bool], range: std::ops::Range<usize>, values: &[u16]) -> bool {
+        match table.get_mut(range) {
+            Some(slice) => {
+                for (bit, value) in slice.iter_mut().zip(values) {
+                    *bit = *value != 0;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn write_words(table: &mut [u16], range: std::ops::Range<usize>, values: &[u16]) -> bool {
+        match table.get_mut(range) {
+            Some(slice) => {
+                slice.copy_from_slice(values);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl RequestHandler for SimulatorHandler {
+    fn read_coil(&self, address: u16) -> std::result::Result<bool, ExceptionCode> {
+        self.coils
+            .get(address as usize)
+            .copied()
+            .ok_or(ExceptionCode::IllegalDataAddress)
+    }
+
+    fn read_discrete_input(&self, address: u16) -> std::result::Result<bool, ExceptionCode> {
+        self.discrete_inputs
+            .get(address as usize)
+            .copied()
+            .ok_or(ExceptionCode::IllegalDataAddress)
+    }
+
+    fn read_holding_register(&self, address: u16) -> std::result::Result<u16, ExceptionCode> {
+        self.holding_registers
+            .get(address as usize)
+            .copied()
+            .ok_or(ExceptionCode::IllegalDataAddress)
+    }
+
+    fn read_input_register(&self, address: u16) -> std::result::Result<u16, ExceptionCode> {
+        self.input_registers
+            .get(address as usize)
+            .copied()
+            .ok_or(ExceptionCode::IllegalDataAddress)
+    }
+
+    fn write_single_coil(
+        &mut self,
+        value: Indexed<bool>,
+    ) -> std::result::Result<(), ExceptionCode> {
+        match self.coils.get_mut(value.index as usize) {
+            Some(coil) => {
+                *coil = value.value;
+                Ok(())
+            }
+            None => Err(ExceptionCode::IllegalDataAddress),
+        }
+    }
+
+    fn write_single_register(
+        &mut self,
+        value: Indexed<u16>,
+    ) -> std::result::Result<(), ExceptionCode> {
+        match self.holding_registers.get_mut(value.index as usize) {
+            Some(register) => {
+                *register = value.value;
+                Ok(())
+            }
+            None => Err(ExceptionCode::IllegalDataAddress),
+        }
+    }
+
+    fn write_multiple_coils(
+        &mut self,
+        values: WriteCoils,
+    ) -> std::result::Result<(), ExceptionCode> {
+        for value in values.iterator {
+            match self.coils.get_mut(value.index as usize) {
+                Some(coil) => *coil = value.value,
+                None => return Err(ExceptionCode::IllegalDataAddress),
+            }
+        }
+        Ok(())
+    }
+
+    fn write_multiple_registers(
+        &mut self,
+        values: WriteRegisters,
+    ) -> std::result::Result<(), ExceptionCode> {
+        for value in values.iterator {
+            match self.holding_registers.get_mut(value.index as usize) {
+                Some(register) => *register = value.value,
+                None => return Err(ExceptionCode::IllegalDataAddress),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct ModbusServer {
+    // Dropping the handle shuts the server task down
+    _handle: ServerHandle,
+    units: HashMap<u8, ServerHandlerType<SimulatorHandler>>,
+}
+
+impl ModbusServer {
+    pub async fn start(
+        server_settings: ModbusServerSettings,
+        unit_ids: Vec<u8>,
+        size: u16,
+    ) -> Result<Self> {
+        let mut units = HashMap::new();
+        let mut handlers = ServerHandlerMap::new();
+        for unit_id in unit_ids {
+            let handler = SimulatorHandler::new(size).wrap();
+            handlers.add(UnitId::new(unit_id), handler.clone());
+            units.insert(unit_id, handler);
+        }
+
+        let handle = match server_settings {
+            ModbusServerSettings::RTU {
+                path,
+                baud_rate,
+                data_bits,
+                parity,
+                stop_bits,
+            } => spawn_rtu_server_task(
+                path.as_str(),
+                rodbus::SerialSettings {
+                    baud_rate,
+                    data_bits,
+                    flow_control: FlowControl::None,
+                    parity,
+                    stop_bits,
+                },
+                default_retry_strategy(),
+                handlers,
+                DecodeLevel::default(),
+            )
+            .map_err(ModbusError::from)?,
+            ModbusServerSettings::TCP { host, port } => {
+                let addr = SocketAddr::new(host.parse()?, port);
+                spawn_tcp_server_task(
+                    10,
+                    addr,
+                    handlers,
+                    AddressFilter::Any,
+                    DecodeLevel::default(),
+                )
+                .await
+                .map_err(ModbusError::from)?
+            }
+        };
+
+        Ok(Self {
+            _handle: handle,
+            units,
+        })
+    }
+
+    pub fn read(
+        &self,
+        unit_id: u8,
+        register_type: RegisterType,
+        start_address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>> {
+        let handler = self.unit(unit_id)?;
+        let handler = handler.lock().map_err(|e| e.to_string())?;
+        handler.read(register_type, start_address, count)
+    }
+
+    pub fn write(
+        &self,
+        unit_id: u8,
+        register_type: RegisterType,
+        start_address: u16,
+        values: Vec<u16>,
+    ) -> Result<()> {
+        let handler = self.unit(unit_id)?;
+        let mut handler = handler.lock().map_err(|e| e.to_string())?;
+        handler.write(register_type, start_address, &values)
+    }
+
+    fn unit(&self, unit_id: u8) -> Result<&ServerHandlerType<SimulatorHandler>> {
+        self.units
+            .get(&unit_id)
+            .ok_or_else(|| Error::invalid_parameter(format!("Unit ID {} is not served", unit_id)))
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::modbus::frame::{rtu_crc_valid, rtu_frame};
+    use std::io::{Read, Write};
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rtu_server_answers_over_pty() {
+        let (mut master, slave) = serialport::TTYPort::pair().expect("failed to open pty pair");
+        let path = slave.name().expect("pty has no name");
+
+        let settings = ModbusServerSettings::RTU {
+            path,
+            baud_rate: 9600,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+        };
+        let server = ModbusServer::start(settings, vec![1], 16).await.unwrap();
+        server
+            .write(1, RegisterType::HoldingRegister, 2, vec![0x1234, 0x5678])
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let request = rtu_frame(1, &[0x03, 0x00, 0x02, 0x00, 0x02]);
+        master.set_timeout(Duration::from_secs(2)).unwrap();
+        master.write_all(&request).unwrap();
+
+        let mut response = [0u8; 9];
+        master.read_exact(&mut response).unwrap();
+        assert_eq!(&response[..7], &[0x01, 0x03, 0x04, 0x12, 0x34, 0x56, 0x78]);
+        assert!(rtu_crc_valid(&response));
+
+        drop(slave);
+    }
+}
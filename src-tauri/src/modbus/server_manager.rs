@@ -0,0 +1,51 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::RwLock;
+
+use crate::modbus::{
+    connection_manager::ConnectionId, gateway::ModbusGateway, server::ModbusServer,
+};
+
+pub type ServerId = u32;
+
+pub struct ModbusServerManager {
+    servers: RwLock<HashMap<ServerId, Arc<ModbusServer>>>,
+    gateways: RwLock<HashMap<ServerId, ModbusGateway>>,
+}
+
+impl ModbusServerManager {
+    pub fn new() -> Self {
+        Self {
+            servers: RwLock::new(HashMap::new()),
+            gateways: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn add_server(&self, id: ServerId, server: Arc<ModbusServer>) {
+        self.servers.write().await.insert(id, server);
+    }
+
+    pub async fn remove_server(&self, id: ServerId) {
+        self.servers.write().await.remove(&id);
+    }
+
+    pub async fn get_server(&self, id: ServerId) -> Option<Arc<ModbusServer>> {
+        self.servers.read().await.get(&id).cloned()
+    }
+
+    pub async fn add_gateway(&self, id: ServerId, gateway: ModbusGateway) {
+        self.gateways.write().await.insert(id, gateway);
+    }
+
+    pub async fn remove_gateway(&self, id: ServerId) {
+        self.gateways.write().await.remove(&id);
+    }
+
+    // Stops the gateways forwarding to a connection that is being closed
+    pub async fn remove_connection_gateways(&self, connection_id: ConnectionId) {
+        self.gateways
+            .write()
+            .await
+            .retain(|_, gateway| gateway.connection_id() != connection_id);
+    }
+}
@@ -0,0 +1,481 @@
+use std::time::{Duration, Instant};
+
+use log::error;
+use rodbus::{AddressRange, Indexed};
+use serde::Serialize;
+use specta::Type;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::{
+    errors::Result,
+    modbus::{
+        connection_manager::ConnectionId,
+        controller::IndexedValue,
+        frame::rtu_crc_valid,
+        pdu::{self, EXCEPTION_FLAG},
+        poller::timestamp_millis,
+        transport::{read_until_silence, SerialSettings},
+    },
+};
+
+pub const SNIFFER_EVENT: &str = "modbus-sniffer";
+
+// Silence the sniffer waits for at least before ending a frame. USB-serial
+// adapters and a non-realtime OS deliver bytes in bursts with pauses well
+// above the 1.75 ms the standard allows at high baud rates.
+pub const DEFAULT_SNIFFER_MIN_GAP: Duration = Duration::from_millis(5);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameRole {
+    Request,
+    Response,
+    // Frames failing the CRC check, usually collisions or noise
+    Invalid,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SniffedFrame {
+    pub connection_id: ConnectionId,
+    // Shared by a request and its response
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub role: FrameRole,
+    pub unit_id: u8,
+    pub function_code: u8,
+    pub exception: Option<String>,
+    // Start address and quantity, taken from the request for responses
+    pub address: Option<u16>,
+    pub quantity: Option<u16>,
+    // Coil and register values carried by the frame
+    pub values: Vec<IndexedValue>,
+    // Complete RTU frame, CRC included
+    pub raw: Vec<u8>,
+    // Whether the frame's CRC was valid as captured
+    pub crc_valid: bool,
+    pub latency_ms: Option<f64>,
+}
+
+struct PendingRequest {
+    sequence: u64,
+    unit_id: u8,
+    pdu: Vec<u8>,
+    received: Instant,
+}
+
+impl PendingRequest {
+    // Whether `response` has the length an answer to this request must
+    // have, so a request repeated after a missed answer is not taken as the
+    // response. Write Single Coil/Register responses echo the request, so an
+    // identical retry still cannot be told apart from one.
+    fn answered_by(&self, unit_id: u8, response: &[u8]) -> bool {
+        let function_code = self.pdu[0];
+        if unit_id != self.unit_id {
+            return false;
+        }
+        if response[0] == function_code | EXCEPTION_FLAG {
+            return response.len() == 2;
+        }
+        if response[0] != function_code {
+            return false;
+        }
+
+        let count = word(&self.pdu, 3).map(usize::from);
+        match function_code {
+            pdu::READ_COILS | pdu::READ_DISCRETE_INPUTS => {
+                count.is_none_or(|count| response.len() == 2 + count.div_ceil(8))
+            }
+            pdu::READ_HOLDING_REGISTERS
+            | pdu::READ_INPUT_REGISTERS
+            | pdu::READ_WRITE_MULTIPLE_REGISTERS => {
+                count.is_none_or(|count| response.len() == 2 + count * 2)
+            }
+            pdu::WRITE_SINGLE_COIL | pdu::WRITE_SINGLE_REGISTER | pdu::MASK_WRITE_REGISTER => {
+                response == self.pdu
+            }
+            pdu::WRITE_MULTIPLE_COILS | pdu::WRITE_MULTIPLE_REGISTERS => response.len() == 5,
+            // Other responses have no length known from the request
+            _ => true,
+        }
+    }
+}
+
+fn word(pdu: &[u8], offset: usize) -> Option<u16> {
+    pdu.get(offset..offset + 2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+// Start address, quantity and values of a frame, left empty for function
+// codes without them and frames that do not parse
+#[derive(Default)]
+struct Decoded {
+    address: Option<u16>,
+    quantity: Option<u16>,
+    values: Vec<IndexedValue>,
+}
+
+fn indexed<T>(values: Result<Vec<Indexed<T>>>) -> Vec<IndexedValue>
+where
+    IndexedValue: From<Indexed<T>>,
+{
+    values
+        .map(|values| values.into_iter().map(IndexedValue::from).collect())
+        .unwrap_or_default()
+}
+
+// Decodes a request or, given one, the response to it
+fn decode(request: &[u8], response: Option<&[u8]>) -> Decoded {
+    let (Some(start), Some(field)) = (word(request, 1), word(request, 3)) else {
+        return Decoded::default();
+    };
+    let range = AddressRange {
+        start,
+        count: field,
+    };
+
+    let values = match (request[0], response) {
+        (pdu::READ_COILS | pdu::READ_DISCRETE_INPUTS, Some(response)) => {
+            indexed(pdu::bits_from_response(range, response))
+        }
+        (
+            pdu::READ_HOLDING_REGISTERS
+            | pdu::READ_INPUT_REGISTERS
+            | pdu::READ_WRITE_MULTIPLE_REGISTERS,
+            Some(response),
+        ) => indexed(pdu::registers_from_response(range, response)),
+        // Past the address, the byte count and values of a multiple write
+        // are laid out like those of a read response
+        (pdu::WRITE_MULTIPLE_COILS, None) => indexed(pdu::bits_from_response(range, &request[4..])),
+        (pdu::WRITE_MULTIPLE_REGISTERS, None) => {
+            indexed(pdu::registers_from_response(range, &request[4..]))
+        }
+        // Read requests and multiple write responses carry no values
+        (
+            pdu::READ_COILS
+            | pdu::READ_DISCRETE_INPUTS
+            | pdu::READ_HOLDING_REGISTERS
+            | pdu::READ_INPUT_REGISTERS
+            | pdu::READ_WRITE_MULTIPLE_REGISTERS
+            | pdu::WRITE_MULTIPLE_COILS
+            | pdu::WRITE_MULTIPLE_REGISTERS,
+            _,
+        ) => Vec::new(),
+        // Single writes carry a value where reads carry the quantity, and are
+        // echoed by the response
+        (pdu::WRITE_SINGLE_COIL | pdu::WRITE_SINGLE_REGISTER, _) => {
+            let value = if request[0] == pdu::WRITE_SINGLE_COIL {
+                u16::from(field == 0xFF00)
+            } else {
+                field
+            };
+            return Decoded {
+                address: Some(start),
+                quantity: Some(1),
+                values: vec![IndexedValue {
+                    address: start,
+                    value,
+                }],
+            };
+        }
+        _ => return Decoded::default(),
+    };
+
+    Decoded {
+        address: Some(start),
+        quantity: Some(field),
+        values,
+    }
+}
+
+// Classifies frames as requests or responses. The bus carries no direction,
+// so a frame answering the previous request's unit ID and function code with
+// the expected length is taken as its response and anything else as a new
+// request.
+pub struct FramePairer {
+    connection_id: ConnectionId,
+    next_sequence: u64,
+    pending: Option<PendingRequest>,
+}
+
+impl FramePairer {
+    pub fn new(connection_id: ConnectionId) -> Self {
+        Self {
+            connection_id,
+            next_sequence: 1,
+            pending: None,
+        }
+    }
+
+    pub fn push(&mut self, raw: Vec<u8>) -> SniffedFrame {
+        let received = Instant::now();
+        let valid = rtu_crc_valid(&raw);
+        let unit_id = raw.first().copied().unwrap_or_default();
+        let function_code = raw.get(1).copied().unwrap_or_default();
+        // Between unit ID and CRC, never empty in a valid frame
+        let frame_pdu = if valid { &raw[1..raw.len() - 2] } else { &[] };
+        let exception = (valid && function_code & EXCEPTION_FLAG != 0)
+            .then(|| pdu::exception_from_code(raw.get(2).copied().unwrap_or_default()).to_string());
+
+        let answered = self
+            .pending
+            .take_if(|pending| valid && pending.answered_by(unit_id, frame_pdu));
+
+        let (role, sequence, latency_ms, decoded) = match answered {
+            _ if !valid => (
+                FrameRole::Invalid,
+                self.sequence(),
+                None,
+                Decoded::default(),
+            ),
+            Some(request) => (
+                FrameRole::Response,
+                request.sequence,
+                Some(received.duration_since(request.received).as_secs_f64() * 1000.0),
+                match exception {
+                    Some(_) => Decoded::default(),
+                    None => decode(&request.pdu, Some(frame_pdu)),
+                },
+            ),
+            None => {
+                let sequence = self.sequence();
+                self.pending = Some(PendingRequest {
+                    sequence,
+                    unit_id,
+                    pdu: frame_pdu.to_vec(),
+                    received,
+                });
+                (FrameRole::Request, sequence, None, decode(frame_pdu, None))
+            }
+        };
+
+        SniffedFrame {
+            connection_id: self.connection_id,
+            sequence,
+            timestamp: timestamp_millis(),
+            role,
+            unit_id,
+            function_code,
+            exception,
+            address: decoded.address,
+            quantity: decoded.quantity,
+            values: decoded.values,
+            raw,
+            crc_valid: valid,
+            latency_ms,
+        }
+    }
+
+    fn sequence(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        sequence
+    }
+}
+
+// Splits the byte stream into frames at silences of `gap` until the
+// reader closes
+pub async fn capture(
+    mut reader: impl AsyncRead + Unpin,
+    gap: Duration,
+    mut pairer: FramePairer,
+    mut on_frame: impl FnMut(SniffedFrame),
+) -> Result<()> {
+    let mut buffer = [0u8; 256];
+    loop {
+        // The bus may stay idle indefinitely before the next frame
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(());
+        }
+
+        let mut frame = buffer[..read].to_vec();
+        read_until_silence(&mut reader, &mut frame, gap).await?;
+        on_frame(pairer.push(frame));
+    }
+}
+
+// Listen-only serial capture, stopped when dropped
+pub struct Sniffer {
+    handle: JoinHandle<()>,
+}
+
+impl Sniffer {
+    pub fn start(
+        app: AppHandle,
+        connection_id: ConnectionId,
+        settings: SerialSettings,
+        min_gap: Duration,
+    ) -> Result<Self> {
+        let port = settings.open()?;
+        let gap = settings.frame_gap().max(min_gap);
+
+        let handle = tauri::async_runtime::spawn(async move {
+            let emit = |frame: SniffedFrame| {
+                if let Err(e) = app.emit(SNIFFER_EVENT, frame) {
+                    error!("Failed to emit sniffer event: {}", e);
+                }
+            };
+            if let Err(e) = capture(port, gap, FramePairer::new(connection_id), emit).await {
+                error!("Sniffer on {} stopped: {}", settings.path, e);
+            }
+        });
+
+        Ok(Self { handle })
+    }
+}
+
+impl Drop for Sniffer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::modbus::frame::rtu_frame;
+    use rodbus::{DataBits, Parity, StopBits};
+    use std::io::Write;
+    use tokio::sync::mpsc;
+
+    // Writes each recorded chunk to the pty, pausing after it
+    fn feed(master: &mut serialport::TTYPort, recording: &[(Vec<u8>, u64)]) {
+        for (bytes, pause_ms) in recording {
+            master.write_all(bytes).unwrap();
+            master.flush().unwrap();
+            std::thread::sleep(Duration::from_millis(*pause_ms));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sniffer_pairs_frames_from_pty() {
+        let (mut master, slave) = serialport::TTYPort::pair().expect("failed to open pty pair");
+        let settings = SerialSettings {
+            path: slave.name().expect("pty has no name"),
+            baud_rate: 9600,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+        };
+        let port = settings.open().unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(capture(
+            port,
+            Duration::from_millis(20),
+            FramePairer::new(1),
+            move |frame| tx.send(frame).unwrap(),
+        ));
+
+        let mut corrupted = rtu_frame(1, &[0x03, 0x00, 0x00, 0x00, 0x01]);
+        *corrupted.last_mut().unwrap() ^= 0xFF;
+        let recording = vec![
+            (rtu_frame(1, &[0x03, 0x00, 0x02, 0x00, 0x01]), 100),
+            (rtu_frame(1, &[0x03, 0x02, 0x12, 0x34]), 100),
+            (rtu_frame(2, &[0x06, 0x00, 0x01, 0x00, 0x05]), 100),
+            (rtu_frame(2, &[0x86, 0x02]), 100),
+            (corrupted, 100),
+        ];
+        tokio::task::spawn_blocking(move || feed(&mut master, &recording))
+            .await
+            .unwrap();
+
+        let mut frames = Vec::new();
+        while frames.len() < 5 {
+            let frame = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+                .await
+                .expect("sniffer did not report a frame")
+                .unwrap();
+            frames.push(frame);
+        }
+        task.abort();
+        drop(slave);
+
+        let roles: Vec<FrameRole> = frames.iter().map(|frame| frame.role).collect();
+        assert_eq!(
+            roles,
+            [
+                FrameRole::Request,
+                FrameRole::Response,
+                FrameRole::Request,
+                FrameRole::Response,
+                FrameRole::Invalid,
+            ]
+        );
+        assert_eq!(frames[0].sequence, frames[1].sequence);
+        assert_eq!(frames[2].sequence, frames[3].sequence);
+        assert_ne!(frames[1].sequence, frames[2].sequence);
+        assert_eq!(frames[1].raw, rtu_frame(1, &[0x03, 0x02, 0x12, 0x34]));
+        assert!(frames[1].latency_ms.is_some());
+        assert_eq!(frames[3].function_code, 0x86);
+        assert!(frames[3].exception.is_some());
+        assert!(frames[4].exception.is_none());
+        assert!(frames[3].crc_valid);
+        assert!(!frames[4].crc_valid);
+    }
+
+    #[test]
+    fn pairer_checks_response_length_and_decodes_values() {
+        let mut pairer = FramePairer::new(1);
+        let read = rtu_frame(1, &[0x03, 0x00, 0x02, 0x00, 0x02]);
+
+        let request = pairer.push(read.clone());
+        assert_eq!(request.role, FrameRole::Request);
+        assert_eq!((request.address, request.quantity), (Some(2), Some(2)));
+        assert!(request.values.is_empty());
+
+        // A retry after a missed answer is not the response
+        let retry = pairer.push(read);
+        assert_eq!(retry.role, FrameRole::Request);
+        assert_ne!(retry.sequence, request.sequence);
+
+        let response = pairer.push(rtu_frame(1, &[0x03, 0x04, 0x00, 0x0A, 0x00, 0x0B]));
+        assert_eq!(response.role, FrameRole::Response);
+        assert_eq!(response.sequence, retry.sequence);
+        assert_eq!(response.address, Some(2));
+        let values: Vec<(u16, u16)> = response
+            .values
+            .iter()
+            .map(|value| (value.address, value.value))
+            .collect();
+        assert_eq!(values, [(2, 10), (3, 11)]);
+
+        let write = pairer.push(rtu_frame(
+            1,
+            &[0x10, 0x00, 0x05, 0x00, 0x02, 0x04, 0x00, 0x01, 0x00, 0x02],
+        ));
+        assert_eq!(write.role, FrameRole::Request);
+        let values: Vec<(u16, u16)> = write
+            .values
+            .iter()
+            .map(|value| (value.address, value.value))
+            .collect();
+        assert_eq!(values, [(5, 1), (6, 2)]);
+        let written = pairer.push(rtu_frame(1, &[0x10, 0x00, 0x05, 0x00, 0x02]));
+        assert_eq!(written.role, FrameRole::Response);
+        assert_eq!((written.address, written.quantity), (Some(5), Some(2)));
+
+        let coil = pairer.push(rtu_frame(1, &[0x05, 0x00, 0x03, 0xFF, 0x00]));
+        assert_eq!(coil.values.len(), 1);
+        assert_eq!((coil.values[0].address, coil.values[0].value), (3, 1));
+    }
+
+    #[tokio::test]
+    async fn capture_caps_frames_on_a_busy_bus() {
+        let bytes = vec![0x55u8; 600];
+        let mut lengths = Vec::new();
+        capture(
+            &bytes[..],
+            Duration::from_millis(20),
+            FramePairer::new(1),
+            |frame| lengths.push(frame.raw.len()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(lengths, [256, 256, 88]);
+    }
+}
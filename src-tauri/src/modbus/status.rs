@@ -0,0 +1,180 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Mutex,
+};
+
+use log::error;
+use rodbus::{
+    client::{ClientState, PortState},
+    RequestError,
+};
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    errors::{Error, ModbusError, Result},
+    modbus::{connection_manager::ConnectionId, poller::timestamp_millis},
+};
+
+pub const CONNECTION_STATUS_EVENT: &str = "modbus-connection-status";
+
+// Unanswered requests in a row after which a transport link counts as lost
+pub const TIMEOUTS_BEFORE_RETRYING: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    // Lost or refused, the connection keeps reconnecting
+    Retrying,
+    Failed,
+    Closed,
+}
+
+impl ConnectionState {
+    fn is_final(&self) -> bool {
+        matches!(self, ConnectionState::Failed | ConnectionState::Closed)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStatus {
+    pub connection_id: ConnectionId,
+    pub state: ConnectionState,
+    pub timestamp: u64,
+    // Why the connection is retrying or failed
+    pub message: Option<String>,
+}
+
+// State of a single connection, reporting transitions to the frontend
+pub struct ConnectionStatusTracker {
+    app: Option<AppHandle>,
+    status: Mutex<ConnectionStatus>,
+    consecutive_timeouts: AtomicU32,
+}
+
+impl ConnectionStatusTracker {
+    pub fn new(connection_id: ConnectionId, app: Option<AppHandle>) -> Self {
+        let tracker = Self {
+            app,
+            status: Mutex::new(ConnectionStatus {
+                connection_id,
+                state: ConnectionState::Connecting,
+                timestamp: timestamp_millis(),
+                message: None,
+            }),
+            consecutive_timeouts: AtomicU32::new(0),
+        };
+        tracker.emit(tracker.status());
+
+        tracker
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, state: ConnectionState, message: Option<String>) {
+        let status = {
+            let mut status = self.status.lock().unwrap();
+            if status.state == state && status.message == message {
+                return;
+            }
+            status.state = state;
+            status.message = message;
+            status.timestamp = timestamp_millis();
+            status.clone()
+        };
+
+        self.emit(status);
+    }
+
+    // Updates are ignored once the connection failed or was closed,
+    // so dropping its channel or a late request does not overwrite the outcome
+    fn update(&self, state: ConnectionState, message: Option<String>) {
+        if !self.status().state.is_final() {
+            self.set(state, message);
+        }
+    }
+
+    pub fn client_state(&self, state: &ClientState) {
+        match state {
+            // Channels are disabled briefly around raw requests
+            ClientState::Disabled => {}
+            // Reconnect attempts keep reporting the reason of the retry
+            ClientState::Connecting if self.status().state == ConnectionState::Retrying => {}
+            ClientState::Connecting => self.update(ConnectionState::Connecting, None),
+            ClientState::Connected => self.update(ConnectionState::Connected, None),
+            ClientState::WaitAfterFailedConnect(delay) => self.update(
+                ConnectionState::Retrying,
+                Some(format!("Connection failed, retrying in {:?}", delay)),
+            ),
+            ClientState::WaitAfterDisconnect(delay) => self.update(
+                ConnectionState::Retrying,
+                Some(format!("Connection lost, retrying in {:?}", delay)),
+            ),
+            ClientState::Shutdown => self.update(ConnectionState::Closed, None),
+        }
+    }
+
+    pub fn port_state(&self, state: &PortState) {
+        match state {
+            // Channels are disabled briefly around raw requests
+            PortState::Disabled => {}
+            PortState::Wait(delay) => self.update(
+                ConnectionState::Retrying,
+                Some(format!("Serial port unavailable, retrying in {:?}", delay)),
+            ),
+            PortState::Open => self.update(ConnectionState::Connected, None),
+            PortState::Shutdown => self.update(ConnectionState::Closed, None),
+        }
+    }
+
+    // Transports connect on demand, so the outcome of each request tells
+    // whether the link is up; I/O errors make the next request reconnect.
+    // A single timeout may concern one device, but an unreachable host or
+    // an unplugged gateway only ever times out, so a run of them is a loss.
+    pub fn request_result<T>(&self, result: &Result<T>) {
+        let timed_out = matches!(
+            result,
+            Err(Error::ModbusError(ModbusError::RequestError(
+                RequestError::ResponseTimeout
+            )))
+        );
+        let timeouts = if timed_out {
+            self.consecutive_timeouts.fetch_add(1, Ordering::Relaxed) + 1
+        } else {
+            self.consecutive_timeouts.store(0, Ordering::Relaxed);
+            0
+        };
+
+        match result {
+            Ok(_) => self.update(ConnectionState::Connected, None),
+            Err(Error::ModbusError(ModbusError::IoError(err))) => self.update(
+                ConnectionState::Retrying,
+                Some(format!("Connection lost, reconnecting: {}", err)),
+            ),
+            Err(_) if timeouts >= TIMEOUTS_BEFORE_RETRYING => self.update(
+                ConnectionState::Retrying,
+                Some(format!(
+                    "No response to {} requests in a row, retrying",
+                    timeouts
+                )),
+            ),
+            // Invalid responses and exceptions still prove the link is up
+            Err(_) if !timed_out => self.update(ConnectionState::Connected, None),
+            Err(_) => {}
+        }
+    }
+
+    fn emit(&self, status: ConnectionStatus) {
+        if let Some(app) = &self.app {
+            if let Err(e) = app.emit(CONNECTION_STATUS_EVENT, status) {
+                error!("Failed to emit connection status event: {}", e);
+            }
+        }
+    }
+}
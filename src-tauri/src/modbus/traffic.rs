@@ -0,0 +1,237 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use log::{error, info};
+use rodbus::{AppDecodeLevel, RequestError};
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    errors::Result,
+    modbus::{connection_manager::ConnectionId, frame::hex, pdu, poller::timestamp_millis},
+};
+
+pub const TRAFFIC_EVENT: &str = "modbus-traffic";
+pub const TRAFFIC_CAPACITY: usize = 1000;
+
+#[derive(Clone, Copy, Debug, Serialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Request,
+    Response,
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TrafficRecord {
+    pub connection_id: ConnectionId,
+    // Shared by a request and its response
+    pub transaction_id: u64,
+    pub timestamp: u64,
+    pub direction: Direction,
+    pub unit_id: u8,
+    pub function_code: u8,
+    // Protocol data unit, without transport framing
+    pub raw: Vec<u8>,
+    // Whether `raw` was rebuilt from the decoded values rather than captured
+    // from the wire, as rodbus does not expose the frames it exchanges
+    pub reconstructed: bool,
+    // Whether the response frame's CRC was valid as received, None when no
+    // CRC came off the wire (requests, TCP framings, rebuilt responses)
+    pub crc_valid: Option<bool>,
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+// A request waiting for its response to be recorded
+pub struct Exchange {
+    transaction_id: u64,
+    unit_id: u8,
+    function_code: u8,
+    started: Instant,
+}
+
+// Bounded per-connection log of request/response PDUs
+pub struct TrafficLog {
+    connection_id: ConnectionId,
+    app: Option<AppHandle>,
+    records: Mutex<VecDeque<TrafficRecord>>,
+    next_transaction_id: AtomicU64,
+    // Protocol logging for connections without a rodbus channel
+    decode_level: Mutex<AppDecodeLevel>,
+}
+
+impl TrafficLog {
+    pub fn new(connection_id: ConnectionId, app: Option<AppHandle>) -> Self {
+        Self {
+            connection_id,
+            app,
+            records: Mutex::new(VecDeque::with_capacity(TRAFFIC_CAPACITY)),
+            next_transaction_id: AtomicU64::new(1),
+            decode_level: Mutex::new(AppDecodeLevel::Nothing),
+        }
+    }
+
+    pub fn set_decode_level(&self, level: AppDecodeLevel) {
+        if let Ok(mut decode_level) = self.decode_level.lock() {
+            *decode_level = level;
+        }
+    }
+
+    pub fn request(&self, unit_id: u8, raw: Vec<u8>) -> Exchange {
+        self.start(unit_id, raw, false)
+    }
+
+    // Records a request sent through the rodbus channel, encoded here from
+    // its parameters
+    pub fn channel_request(&self, unit_id: u8, raw: Vec<u8>) -> Exchange {
+        self.start(unit_id, raw, true)
+    }
+
+    fn start(&self, unit_id: u8, raw: Vec<u8>, reconstructed: bool) -> Exchange {
+        let exchange = Exchange {
+            transaction_id: self.next_transaction_id.fetch_add(1, Ordering::Relaxed),
+            unit_id,
+            function_code: raw.first().copied().unwrap_or_default(),
+            started: Instant::now(),
+        };
+
+        self.push(TrafficRecord {
+            connection_id: self.connection_id,
+            transaction_id: exchange.transaction_id,
+            timestamp: timestamp_millis(),
+            direction: Direction::Request,
+            unit_id,
+            function_code: exchange.function_code,
+            raw,
+            reconstructed,
+            crc_valid: None,
+            latency_ms: None,
+            error: None,
+        });
+
+        exchange
+    }
+
+    // Records the outcome of a rodbus request, encoding the response PDU
+    // from the decoded values
+    pub fn response<T>(
+        &self,
+        exchange: Exchange,
+        result: &std::result::Result<T, RequestError>,
+        encode: impl FnOnce(&T) -> Vec<u8>,
+    ) {
+        let (raw, error) = match result {
+            Ok(value) => (encode(value), None),
+            Err(RequestError::Exception(code)) => (
+                pdu::exception_response(exchange.function_code, *code),
+                Some(code.to_string()),
+            ),
+            Err(err) => (Vec::new(), Some(err.to_string())),
+        };
+
+        self.push(TrafficRecord {
+            connection_id: self.connection_id,
+            transaction_id: exchange.transaction_id,
+            timestamp: timestamp_millis(),
+            direction: Direction::Response,
+            unit_id: exchange.unit_id,
+            function_code: raw.first().copied().unwrap_or(exchange.function_code),
+            raw,
+            reconstructed: true,
+            crc_valid: None,
+            latency_ms: Some(exchange.started.elapsed().as_secs_f64() * 1000.0),
+            error,
+        });
+    }
+
+    // Records a response PDU received outside of the rodbus channel, with
+    // the CRC status of its frame on RTU framings
+    pub fn pdu_response(
+        &self,
+        exchange: Exchange,
+        result: &Result<Vec<u8>>,
+        crc_valid: Option<bool>,
+    ) {
+        let (raw, error) = match result {
+            Ok(response)
+                if response.first() == Some(&(exchange.function_code | pdu::EXCEPTION_FLAG)) =>
+            {
+                let code = pdu::exception_from_code(response.get(1).copied().unwrap_or_default());
+                (response.clone(), Some(code.to_string()))
+            }
+            Ok(response) => (response.clone(), None),
+            Err(err) => (Vec::new(), Some(err.to_string())),
+        };
+
+        self.push(TrafficRecord {
+            connection_id: self.connection_id,
+            transaction_id: exchange.transaction_id,
+            timestamp: timestamp_millis(),
+            direction: Direction::Response,
+            unit_id: exchange.unit_id,
+            function_code: raw.first().copied().unwrap_or(exchange.function_code),
+            raw,
+            reconstructed: false,
+            crc_valid,
+            latency_ms: Some(exchange.started.elapsed().as_secs_f64() * 1000.0),
+            error,
+        });
+    }
+
+    pub fn records(&self) -> Vec<TrafficRecord> {
+        self.records
+            .lock()
+            .map(|records| records.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut records) = self.records.lock() {
+            records.clear();
+        }
+    }
+
+    fn log(&self, record: &TrafficRecord) {
+        let level = self
+            .decode_level
+            .lock()
+            .map(|level| *level)
+            .unwrap_or(AppDecodeLevel::Nothing);
+        let summary = format!(
+            "Connection {} {:?} unit {} function {:#04X}",
+            record.connection_id, record.direction, record.unit_id, record.function_code
+        );
+
+        match level {
+            AppDecodeLevel::Nothing => {}
+            AppDecodeLevel::FunctionCode => info!("{}", summary),
+            AppDecodeLevel::DataHeaders | AppDecodeLevel::DataValues => {
+                info!("{}: {}", summary, hex(&record.raw))
+            }
+        }
+    }
+
+    fn push(&self, record: TrafficRecord) {
+        self.log(&record);
+        if let Some(app) = &self.app {
+            if let Err(e) = app.emit(TRAFFIC_EVENT, record.clone()) {
+                error!("Failed to emit traffic event: {}", e);
+            }
+        }
+
+        if let Ok(mut records) = self.records.lock() {
+            if records.len() == TRAFFIC_CAPACITY {
+                records.pop_front();
+            }
+            records.push_back(record);
+        }
+    }
+}
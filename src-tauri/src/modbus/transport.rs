@@ -0,0 +1,777 @@
+use std::{io::ErrorKind, net::SocketAddr, time::Duration};
+
+use rodbus::{client::Channel, DataBits, Parity, StopBits};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+    sync::Mutex,
+};
+use tokio_serial::{ClearBuffer, SerialPort, SerialPortBuilderExt, SerialStream};
+
+use crate::{
+    errors::{Error, ModbusError, Result},
+    modbus::{
+        frame::{
+            ascii_frame, mbap_frame, parse_ascii_frame, parse_mbap_frame, rtu_crc_valid, rtu_frame,
+            MAX_RTU_FRAME_LENGTH,
+        },
+        pdu::invalid_response,
+    },
+};
+
+// Default silence that ends an RTU-over-TCP response
+pub const DEFAULT_RTU_OVER_TCP_FRAME_GAP: Duration = Duration::from_millis(50);
+
+// Default number of times an unanswered UDP request is sent again
+pub const DEFAULT_UDP_RETRANSMISSIONS: u32 = 2;
+
+// Slaves act on a broadcast without answering, so the line is kept quiet
+// for a while before the next request
+pub const BROADCAST_TURNAROUND: Duration = Duration::from_millis(100);
+
+// Carries the function codes a rodbus channel does not implement, and every
+// request of connections that are not built on one
+#[async_trait::async_trait]
+pub trait PduTransport: Send {
+    async fn transact(&mut self, unit_id: u8, request: &[u8], timeout: Duration)
+        -> Result<Vec<u8>>;
+
+    // Sends a request to unit 0, which every slave on a serial line executes
+    async fn broadcast(&mut self, _request: &[u8], _timeout: Duration) -> Result<()> {
+        Err(Error::invalid_parameter(
+            "Broadcasts are only supported on serial lines",
+        ))
+    }
+
+    // Whether the CRC of the last response frame was valid as received,
+    // None for framings without a CRC or when no frame arrived
+    fn last_crc_valid(&self) -> Option<bool> {
+        None
+    }
+}
+
+// A connection's transport; holding the lock keeps other requests off the line
+pub type SharedTransport = Mutex<Box<dyn PduTransport>>;
+
+pub fn shared(transport: impl PduTransport + 'static) -> SharedTransport {
+    Mutex::new(Box::new(transport))
+}
+
+fn timeout_error() -> Error {
+    Error::ModbusError(ModbusError::RequestError(
+        rodbus::RequestError::ResponseTimeout,
+    ))
+}
+
+// Modbus TCP framing (MBAP header) over a lazily opened stream
+pub struct TcpTransport {
+    addr: SocketAddr,
+    stream: Option<TcpStream>,
+    next_transaction_id: u16,
+}
+
+impl TcpTransport {
+    pub fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            stream: None,
+            next_transaction_id: 1,
+        }
+    }
+
+    async fn exchange(
+        stream: &mut TcpStream,
+        transaction_id: u16,
+        unit_id: u8,
+        request: &[u8],
+    ) -> Result<Vec<u8>> {
+        stream
+            .write_all(&mbap_frame(transaction_id, unit_id, request))
+            .await?;
+
+        // Responses to earlier requests are skipped
+        loop {
+            let mut frame = vec![0u8; 7];
+            stream.read_exact(&mut frame).await?;
+            let length = u16::from_be_bytes([frame[4], frame[5]]) as usize;
+            if !(2..=254).contains(&length) {
+                return Err(invalid_response("Invalid MBAP length"));
+            }
+
+            frame.resize(6 + length, 0);
+            stream.read_exact(&mut frame[7..]).await?;
+            let (response_transaction_id, response_unit_id, pdu) = parse_mbap_frame(&frame)?;
+            if response_transaction_id != transaction_id {
+                continue;
+            }
+            if response_unit_id != unit_id {
+                return Err(invalid_response("Unexpected unit ID"));
+            }
+            return Ok(pdu);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PduTransport for TcpTransport {
+    async fn transact(
+        &mut self,
+        unit_id: u8,
+        request: &[u8],
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        let transaction_id = self.next_transaction_id;
+        self.next_transaction_id = transaction_id.wrapping_add(1);
+
+        let (addr, stream) = (self.addr, &mut self.stream);
+        let result = tokio::time::timeout(timeout, async {
+            if stream.is_none() {
+                *stream = Some(TcpStream::connect(addr).await?);
+            }
+            let connected = stream.as_mut().expect("stream was just opened");
+            Self::exchange(connected, transaction_id, unit_id, request).await
+        })
+        .await
+        .unwrap_or_else(|_| Err(timeout_error()));
+
+        // A request cut off mid-frame leaves the stream out of sync, so the
+        // next request reconnects
+        if result.is_err() {
+            self.stream = None;
+        }
+        result
+    }
+}
+
+// Modbus TCP framing (MBAP header) in UDP datagrams
+pub struct UdpTransport {
+    addr: SocketAddr,
+    retransmissions: u32,
+    socket: Option<UdpSocket>,
+    next_transaction_id: u16,
+}
+
+impl UdpTransport {
+    pub fn new(addr: SocketAddr, retransmissions: u32) -> Self {
+        Self {
+            addr,
+            retransmissions,
+            socket: None,
+            next_transaction_id: 1,
+        }
+    }
+
+    // Datagrams for other transactions, e.g. late answers to earlier
+    // requests, are skipped; a malformed answer to this one is rejected
+    async fn receive(socket: &UdpSocket, transaction_id: u16, unit_id: u8) -> Result<Vec<u8>> {
+        let mut buffer = [0u8; 260];
+        loop {
+            let read = socket.recv(&mut buffer).await?;
+            if read < 2 || u16::from_be_bytes([buffer[0], buffer[1]]) != transaction_id {
+                continue;
+            }
+
+            let (_, response_unit_id, pdu) = parse_mbap_frame(&buffer[..read])?;
+            if response_unit_id != unit_id {
+                return Err(invalid_response("Unexpected unit ID"));
+            }
+            return Ok(pdu);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PduTransport for UdpTransport {
+    async fn transact(
+        &mut self,
+        unit_id: u8,
+        request: &[u8],
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        let socket = &mut self.socket;
+        if socket.is_none() {
+            let local: SocketAddr = if self.addr.is_ipv4() {
+                ([0, 0, 0, 0], 0).into()
+            } else {
+                ([0u16; 8], 0).into()
+            };
+            let bound = UdpSocket::bind(local).await?;
+            bound.connect(self.addr).await?;
+            *socket = Some(bound);
+        }
+        let bound = socket.as_ref().expect("socket was just bound");
+
+        // Retransmissions keep the transaction ID, so a late answer to an
+        // earlier attempt still completes the request
+        let transaction_id = self.next_transaction_id;
+        self.next_transaction_id = transaction_id.wrapping_add(1);
+        let frame = mbap_frame(transaction_id, unit_id, request);
+        for _ in 0..=self.retransmissions {
+            let result = match bound.send(&frame).await {
+                Ok(_) => {
+                    tokio::time::timeout(timeout, Self::receive(bound, transaction_id, unit_id))
+                        .await
+                }
+                Err(err) => Ok(Err(err.into())),
+            };
+            match result {
+                Ok(Ok(response)) => return Ok(response),
+                Ok(Err(err)) => {
+                    // Rebind on the next request, e.g. after an ICMP error
+                    *socket = None;
+                    return Err(err);
+                }
+                Err(_) => {}
+            }
+        }
+
+        Err(timeout_error())
+    }
+}
+
+#[derive(Clone)]
+pub struct SerialSettings {
+    pub path: String,
+    pub baud_rate: u32,
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+}
+
+impl SerialSettings {
+    pub fn open(&self) -> Result<SerialStream> {
+        let data_bits = match self.data_bits {
+            DataBits::Five => tokio_serial::DataBits::Five,
+            DataBits::Six => tokio_serial::DataBits::Six,
+            DataBits::Seven => tokio_serial::DataBits::Seven,
+            DataBits::Eight => tokio_serial::DataBits::Eight,
+        };
+        let parity = match self.parity {
+            Parity::None => tokio_serial::Parity::None,
+            Parity::Odd => tokio_serial::Parity::Odd,
+            Parity::Even => tokio_serial::Parity::Even,
+        };
+        let stop_bits = match self.stop_bits {
+            StopBits::One => tokio_serial::StopBits::One,
+            StopBits::Two => tokio_serial::StopBits::Two,
+        };
+
+        tokio_serial::new(self.path.as_str(), self.baud_rate)
+            .data_bits(data_bits)
+            .parity(parity)
+            .stop_bits(stop_bits)
+            .open_native_async()
+            .map_err(|e| Error::from(std::io::Error::from(e)))
+    }
+
+    // Time to transmit a single character (start, data, parity and stop bits)
+    pub fn char_time(&self) -> Duration {
+        let bits =
+            1 + match self.data_bits {
+                DataBits::Five => 5,
+                DataBits::Six => 6,
+                DataBits::Seven => 7,
+                DataBits::Eight => 8,
+            } + if matches!(self.parity, Parity::None) {
+                0
+            } else {
+                1
+            } + if matches!(self.stop_bits, StopBits::One) {
+                1
+            } else {
+                2
+            };
+
+        Duration::from_secs_f64(bits as f64 / self.baud_rate as f64)
+    }
+
+    // Inter-frame silence; fixed at 1.75 ms above 19200 baud
+    pub fn frame_gap(&self) -> Duration {
+        if self.baud_rate > 19200 {
+            Duration::from_micros(1750)
+        } else {
+            self.char_time().mul_f64(3.5)
+        }
+    }
+}
+
+// Reads bytes until the line stays silent for `gap` after the first byte
+pub async fn read_silence_delimited(
+    reader: &mut (impl AsyncRead + Unpin),
+    gap: Duration,
+    timeout: Duration,
+) -> Result<Vec<u8>> {
+    let mut frame = Vec::new();
+    let mut buffer = [0u8; 256];
+
+    let read = tokio::time::timeout(timeout, reader.read(&mut buffer))
+        .await
+        .map_err(|_| timeout_error())??;
+    if read == 0 {
+        return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into());
+    }
+    frame.extend_from_slice(&buffer[..read]);
+
+    read_until_silence(reader, &mut frame, gap).await?;
+    Ok(frame)
+}
+
+// Appends to `frame` until nothing arrives for `gap`, or until it holds the
+// longest valid RTU frame on a bus that never goes silent
+pub async fn read_until_silence(
+    reader: &mut (impl AsyncRead + Unpin),
+    frame: &mut Vec<u8>,
+    gap: Duration,
+) -> Result<()> {
+    let mut buffer = [0u8; MAX_RTU_FRAME_LENGTH];
+    loop {
+        let room = MAX_RTU_FRAME_LENGTH.saturating_sub(frame.len());
+        if room == 0 {
+            return Ok(());
+        }
+        match tokio::time::timeout(gap, reader.read(&mut buffer[..room])).await {
+            Ok(Ok(0)) | Err(_) => return Ok(()),
+            Ok(Ok(read)) => frame.extend_from_slice(&buffer[..read]),
+            Ok(Err(err)) => return Err(err.into()),
+        }
+    }
+}
+
+// PDU of an RTU response frame addressed from `unit_id`
+fn rtu_pdu(unit_id: u8, frame: &[u8]) -> Result<Vec<u8>> {
+    if !rtu_crc_valid(frame) {
+        return Err(invalid_response("CRC check failed"));
+    }
+    if frame[0] != unit_id {
+        return Err(invalid_response("Unexpected unit ID"));
+    }
+
+    Ok(frame[1..frame.len() - 2].to_vec())
+}
+
+// RTU framing on a serial port, opened on the first request
+pub struct RtuTransport {
+    settings: SerialSettings,
+    port: Option<SerialStream>,
+    crc_valid: Option<bool>,
+}
+
+impl RtuTransport {
+    pub fn new(settings: SerialSettings) -> Self {
+        Self {
+            settings,
+            port: None,
+            crc_valid: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PduTransport for RtuTransport {
+    async fn transact(
+        &mut self,
+        unit_id: u8,
+        request: &[u8],
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        self.crc_valid = None;
+        if self.port.is_none() {
+            self.port = Some(self.settings.open()?);
+        }
+        let port = self.port.as_mut().expect("port was just opened");
+
+        // Late responses to timed out requests would be read as this response
+        let result = match port.clear(ClearBuffer::Input) {
+            Ok(()) => match port.write_all(&rtu_frame(unit_id, request)).await {
+                Ok(()) => read_silence_delimited(port, self.settings.frame_gap(), timeout).await,
+                Err(err) => Err(err.into()),
+            },
+            Err(err) => Err(Error::from(std::io::Error::from(err))),
+        };
+
+        match result {
+            Ok(frame) => {
+                self.crc_valid = Some(rtu_crc_valid(&frame));
+                rtu_pdu(unit_id, &frame)
+            }
+            Err(Error::ModbusError(ModbusError::IoError(err))) => {
+                // Reopen the port on the next request, e.g. after a USB
+                // adapter was unplugged
+                self.port = None;
+                Err(err.into())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn broadcast(&mut self, request: &[u8], _timeout: Duration) -> Result<()> {
+        if self.port.is_none() {
+            self.port = Some(self.settings.open()?);
+        }
+        let port = self.port.as_mut().expect("port was just opened");
+
+        if let Err(err) = port.write_all(&rtu_frame(0, request)).await {
+            self.port = None;
+            return Err(err.into());
+        }
+        tokio::time::sleep(BROADCAST_TURNAROUND).await;
+        Ok(())
+    }
+
+    fn last_crc_valid(&self) -> Option<bool> {
+        self.crc_valid
+    }
+}
+
+// RTU framing on a serial port that a rodbus channel also uses. A disabled
+// channel closes the port, so the channel is disabled for each raw request
+// and the port is closed again before the channel resumes.
+pub struct ChannelPortTransport {
+    channel: Channel,
+    transport: RtuTransport,
+}
+
+impl ChannelPortTransport {
+    pub fn new(channel: Channel, settings: SerialSettings) -> Self {
+        Self {
+            channel,
+            transport: RtuTransport::new(settings),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PduTransport for ChannelPortTransport {
+    async fn transact(
+        &mut self,
+        unit_id: u8,
+        request: &[u8],
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        self.channel.disable().await?;
+        let result = self.transport.transact(unit_id, request, timeout).await;
+        self.transport.port = None;
+        self.channel.enable().await?;
+
+        result
+    }
+
+    async fn broadcast(&mut self, request: &[u8], timeout: Duration) -> Result<()> {
+        self.channel.disable().await?;
+        let result = self.transport.broadcast(request, timeout).await;
+        self.transport.port = None;
+        self.channel.enable().await?;
+
+        result
+    }
+    fn last_crc_valid(&self) -> Option<bool> {
+        self.transport.last_crc_valid()
+    }
+}
+
+// Modbus ASCII framing on a serial port held open for the connection
+pub struct AsciiTransport {
+    settings: SerialSettings,
+    port: Option<SerialStream>,
+}
+
+impl AsciiTransport {
+    pub fn new(settings: SerialSettings) -> Self {
+        Self {
+            settings,
+            port: None,
+        }
+    }
+
+    // Skips anything before the colon, then reads up to the line feed
+    async fn read_frame(port: &mut SerialStream) -> Result<Vec<u8>> {
+        let mut frame = Vec::new();
+        let mut byte = [0u8; 1];
+
+        loop {
+            if port.read(&mut byte).await? == 0 {
+                return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into());
+            }
+            match byte[0] & 0x7F {
+                b':' => frame = vec![byte[0]],
+                _ if frame.is_empty() => {}
+                b'\n' => {
+                    frame.push(byte[0]);
+                    return Ok(frame);
+                }
+                _ => frame.push(byte[0]),
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PduTransport for AsciiTransport {
+    async fn transact(
+        &mut self,
+        unit_id: u8,
+        request: &[u8],
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        let port = &mut self.port;
+        if port.is_none() {
+            *port = Some(self.settings.open()?);
+        }
+        let opened = port.as_mut().expect("port was just opened");
+
+        // Late responses to timed out requests would match this request
+        let sent = match opened.clear(ClearBuffer::Input) {
+            Ok(()) => opened.write_all(&ascii_frame(unit_id, request)).await,
+            Err(err) => Err(err.into()),
+        };
+        if let Err(err) = sent {
+            // Reopen the port on the next request, e.g. after a USB
+            // adapter was unplugged
+            *port = None;
+            return Err(err.into());
+        }
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let frame = tokio::time::timeout_at(deadline, Self::read_frame(opened))
+                .await
+                .map_err(|_| timeout_error())?;
+            let (response_unit_id, pdu) = match frame {
+                Ok(frame) => parse_ascii_frame(&frame)?,
+                Err(err) => {
+                    // Reopen the port on the next request
+                    *port = None;
+                    return Err(err);
+                }
+            };
+
+            // Frames from other stations on the bus are skipped
+            if response_unit_id == unit_id {
+                return Ok(pdu);
+            }
+        }
+    }
+
+    async fn broadcast(&mut self, request: &[u8], _timeout: Duration) -> Result<()> {
+        if self.port.is_none() {
+            self.port = Some(self.settings.open()?);
+        }
+        let port = self.port.as_mut().expect("port was just opened");
+
+        if let Err(err) = port.write_all(&ascii_frame(0, request)).await {
+            self.port = None;
+            return Err(err.into());
+        }
+        tokio::time::sleep(BROADCAST_TURNAROUND).await;
+        Ok(())
+    }
+}
+
+// RTU frames, CRC included, carried over a TCP stream as forwarded by
+// serial device servers
+pub struct RtuOverTcpTransport {
+    addr: SocketAddr,
+    // Gateways may split a frame across TCP segments and delay them,
+    // so this is much wider than the serial inter-frame gap
+    frame_gap: Duration,
+    stream: Option<TcpStream>,
+    crc_valid: Option<bool>,
+}
+
+impl RtuOverTcpTransport {
+    pub fn new(addr: SocketAddr, frame_gap: Duration) -> Self {
+        Self {
+            addr,
+            frame_gap,
+            stream: None,
+            crc_valid: None,
+        }
+    }
+
+    // Late responses to timed out requests would be read as the next response
+    fn discard_pending(stream: &TcpStream) -> Result<()> {
+        let mut buffer = [0u8; 256];
+        loop {
+            match stream.try_read(&mut buffer) {
+                Ok(0) => return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into()),
+                Ok(_) => continue,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PduTransport for RtuOverTcpTransport {
+    async fn transact(
+        &mut self,
+        unit_id: u8,
+        request: &[u8],
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        self.crc_valid = None;
+        let (addr, frame_gap, stream) = (self.addr, self.frame_gap, &mut self.stream);
+        let result = tokio::time::timeout(timeout, async {
+            if stream.is_none() {
+                *stream = Some(TcpStream::connect(addr).await?);
+            }
+            let connected = stream.as_mut().expect("stream was just opened");
+            Self::discard_pending(connected)?;
+            connected.write_all(&rtu_frame(unit_id, request)).await?;
+            read_silence_delimited(connected, frame_gap, timeout).await
+        })
+        .await
+        .unwrap_or_else(|_| Err(timeout_error()));
+
+        match result {
+            Ok(frame) => {
+                self.crc_valid = Some(rtu_crc_valid(&frame));
+                rtu_pdu(unit_id, &frame)
+            }
+            Err(err) => {
+                // The gateway may still be sending the timed out response,
+                // so the next request starts on a fresh stream
+                self.stream = None;
+                Err(err)
+            }
+        }
+    }
+
+    async fn broadcast(&mut self, request: &[u8], timeout: Duration) -> Result<()> {
+        let (addr, stream) = (self.addr, &mut self.stream);
+        let result = tokio::time::timeout(timeout, async {
+            if stream.is_none() {
+                *stream = Some(TcpStream::connect(addr).await?);
+            }
+            let connected = stream.as_mut().expect("stream was just opened");
+            Self::discard_pending(connected)?;
+            connected.write_all(&rtu_frame(0, request)).await?;
+            Ok(())
+        })
+        .await
+        .unwrap_or_else(|_| Err(timeout_error()));
+
+        if result.is_err() {
+            self.stream = None;
+        }
+        tokio::time::sleep(BROADCAST_TURNAROUND).await;
+        result
+    }
+
+    fn last_crc_valid(&self) -> Option<bool> {
+        self.crc_valid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    // Answers each request with the frames `respond` builds from its transaction ID
+    async fn spawn_tcp_server(respond: fn(u16) -> Vec<Vec<u8>>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = [0u8; 260];
+            loop {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                for frame in respond(u16::from_be_bytes([buffer[0], buffer[1]])) {
+                    socket.write_all(&frame).await.unwrap();
+                }
+            }
+        });
+
+        addr
+    }
+
+    async fn read_register(addr: SocketAddr) -> Result<Vec<u8>> {
+        TcpTransport::new(addr)
+            .transact(1, &[0x03, 0x00, 0x00, 0x00, 0x01], Duration::from_secs(1))
+            .await
+    }
+
+    fn is_invalid_response(result: Result<Vec<u8>>) -> bool {
+        matches!(
+            result,
+            Err(Error::ModbusError(ModbusError::InvalidResponse(_)))
+        )
+    }
+
+    #[tokio::test]
+    async fn tcp_skips_responses_to_earlier_requests() {
+        let addr = spawn_tcp_server(|transaction_id| {
+            vec![
+                mbap_frame(transaction_id.wrapping_sub(1), 1, &[0x03, 0x02, 0x00, 0x01]),
+                mbap_frame(transaction_id, 1, &[0x03, 0x02, 0x12, 0x34]),
+            ]
+        })
+        .await;
+
+        assert_eq!(
+            read_register(addr).await.unwrap(),
+            vec![0x03, 0x02, 0x12, 0x34]
+        );
+    }
+
+    #[tokio::test]
+    async fn tcp_rejects_nonzero_protocol_id() {
+        let addr = spawn_tcp_server(|transaction_id| {
+            let mut frame = mbap_frame(transaction_id, 1, &[0x03, 0x02, 0x12, 0x34]);
+            frame[3] = 0x01;
+            vec![frame]
+        })
+        .await;
+
+        assert!(is_invalid_response(read_register(addr).await));
+    }
+
+    #[tokio::test]
+    async fn tcp_rejects_wrong_unit_id() {
+        let addr = spawn_tcp_server(|transaction_id| {
+            vec![mbap_frame(transaction_id, 2, &[0x03, 0x02, 0x12, 0x34])]
+        })
+        .await;
+
+        assert!(is_invalid_response(read_register(addr).await));
+    }
+
+    #[tokio::test]
+    async fn tcp_rejects_invalid_length() {
+        let addr = spawn_tcp_server(|transaction_id| {
+            let mut frame = mbap_frame(transaction_id, 1, &[0x03, 0x02, 0x12, 0x34]);
+            frame[4..6].copy_from_slice(&1u16.to_be_bytes());
+            vec![frame]
+        })
+        .await;
+
+        assert!(is_invalid_response(read_register(addr).await));
+    }
+
+    #[tokio::test]
+    async fn udp_rejects_length_that_disagrees_with_the_datagram() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buffer = [0u8; 260];
+            let (_, peer) = server.recv_from(&mut buffer).await.unwrap();
+            let mut frame = mbap_frame(
+                u16::from_be_bytes([buffer[0], buffer[1]]),
+                1,
+                &[0x03, 0x02, 0x12, 0x34],
+            );
+            frame[5] += 1;
+            server.send_to(&frame, peer).await.unwrap();
+        });
+
+        let result = UdpTransport::new(addr, 0)
+            .transact(1, &[0x03, 0x00, 0x00, 0x00, 0x01], Duration::from_secs(1))
+            .await;
+        assert!(is_invalid_response(result));
+    }
+}
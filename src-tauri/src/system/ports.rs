@@ -1,4 +1,5 @@
 #[tauri::command]
+#[specta::specta]
 pub fn get_ports() -> Result<Vec<String>, String> {
     let ports = serialport::available_ports().map_err(|e| e.to_string())?;
     let ports_names = ports
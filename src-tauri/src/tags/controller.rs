@@ -0,0 +1,231 @@
+use std::time::Duration;
+
+use crate::{
+    app_state::registry::AppState,
+    errors::{CommandError, Error, TagError},
+    modbus::{
+        connection::MAX_READ_REGISTERS,
+        decoding::{self, TypedValue},
+        planner::{self, ReadPoint, DEFAULT_MAX_GAP},
+    },
+    tags::tag::{Tag, TagValue},
+};
+use log::info;
+use rodbus::Indexed;
+use tauri::State;
+
+// Tags belong to the device behind a connection, so they follow it to
+// whichever tab it is opened in
+async fn device_identity(app_state: &AppState, id: u32) -> Result<String, CommandError> {
+    app_state
+        .connection_manager()
+        .identity(id)
+        .await
+        .ok_or_else(|| Error::connection_not_found(id).into())
+}
+
+// Tag Database Commands
+#[tauri::command]
+#[specta::specta]
+pub async fn list_tags(app_state: State<'_, AppState>, id: u32) -> Result<Vec<Tag>, CommandError> {
+    let device = device_identity(&app_state, id).await?;
+
+    Ok(app_state.tag_manager().list_tags(&device).await)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn save_tag(
+    app_state: State<'_, AppState>,
+    id: u32,
+    tag: Tag,
+) -> Result<(), CommandError> {
+    info!("Saving tag {} for connection {}", tag.name, id);
+    let register_count = tag.register_count();
+    if register_count == 0 || register_count > MAX_READ_REGISTERS || tag.scale == 0.0 {
+        return Err(Error::invalid_parameter("Invalid tag definition").into());
+    }
+    let device = device_identity(&app_state, id).await?;
+
+    app_state
+        .tag_manager()
+        .upsert_tag(&device, tag)
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_tag(
+    app_state: State<'_, AppState>,
+    id: u32,
+    name: String,
+) -> Result<(), CommandError> {
+    info!("Deleting tag {} for connection {}", name, id);
+    let device = device_identity(&app_state, id).await?;
+    app_state
+        .tag_manager()
+        .delete_tag(&device, &name)
+        .await
+        .map_err(|e| e.on_connection(id))
+}
+
+// Read/Write By Tag Commands
+#[tauri::command]
+#[specta::specta]
+pub async fn read_tag(
+    app_state: State<'_, AppState>,
+    id: u32,
+    name: String,
+    timeout: Option<u64>,
+) -> Result<TagValue, CommandError> {
+    info!("Reading tag {} from Modbus", name);
+    let device = device_identity(&app_state, id).await?;
+    let tag = app_state
+        .tag_manager()
+        .get_tag(&device, &name)
+        .await
+        .map_err(|e| e.on_connection(id))?;
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let result = connection
+        .read_table(
+            tag.slave_id,
+            tag.register_type,
+            tag.address,
+            tag.register_count(),
+            timeout.map(Duration::from_millis),
+        )
+        .await
+        .map_err(|e| e.on_connection(id))?;
+
+    let registers: Vec<u16> = result.into_iter().map(|indexed| indexed.value).collect();
+
+    Ok(TagValue {
+        value: tag.decode(&registers).map_err(|e| e.on_connection(id))?,
+        name: tag.name,
+        unit: tag.unit,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn read_tags(
+    app_state: State<'_, AppState>,
+    id: u32,
+    names: Vec<String>,
+    max_gap: Option<u16>,
+    timeout: Option<u64>,
+) -> Result<Vec<TagValue>, CommandError> {
+    info!("Reading {} tags from Modbus", names.len());
+    let device = device_identity(&app_state, id).await?;
+    let mut tags = Vec::with_capacity(names.len());
+    for name in &names {
+        let tag = app_state
+            .tag_manager()
+            .get_tag(&device, name)
+            .await
+            .map_err(|e| e.on_connection(id))?;
+        tags.push(tag);
+    }
+
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let points: Vec<ReadPoint> = tags.iter().map(Tag::read_point).collect();
+    let reads = planner::plan_reads(&points, max_gap.unwrap_or(DEFAULT_MAX_GAP))
+        .map_err(|e| e.on_connection(id))?;
+
+    let mut results = Vec::with_capacity(reads.len());
+    for read in &reads {
+        let values = connection
+            .read_table(
+                read.slave_id,
+                read.register_type,
+                read.start_address,
+                read.count,
+                timeout.map(Duration::from_millis),
+            )
+            .await
+            .map_err(|e| e.on_connection(id))?;
+        results.push(
+            values
+                .into_iter()
+                .map(|indexed| indexed.value)
+                .collect::<Vec<u16>>(),
+        );
+    }
+
+    tags.into_iter()
+        .zip(points)
+        .map(|(tag, point)| -> Result<TagValue, CommandError> {
+            let (read, registers) = reads
+                .iter()
+                .zip(&results)
+                .find(|(read, _)| read.contains(&point))
+                .ok_or_else(|| Error::from("Tag was not covered by any read".to_string()))?;
+            let offset = (point.address - read.start_address) as usize;
+            let registers = &registers[offset..offset + point.count as usize];
+
+            Ok(TagValue {
+                value: tag.decode(registers).map_err(|e| e.on_connection(id))?,
+                name: tag.name,
+                unit: tag.unit,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn write_tag(
+    app_state: State<'_, AppState>,
+    id: u32,
+    name: String,
+    value: TypedValue,
+    timeout: Option<u64>,
+) -> Result<(), CommandError> {
+    info!("Writing tag {} to Modbus", name);
+    let device = device_identity(&app_state, id).await?;
+    let tag = app_state
+        .tag_manager()
+        .get_tag(&device, &name)
+        .await
+        .map_err(|e| e.on_connection(id))?;
+    if !tag.is_writable() {
+        return Err(Error::from(TagError::ReadOnly(name)).on_connection(id));
+    }
+
+    let connection = app_state
+        .connection_manager()
+        .get_connection(id)
+        .await
+        .ok_or(Error::connection_not_found(id))?;
+    let timeout = timeout.map(Duration::from_millis);
+    let raw = tag.to_raw(value);
+
+    if tag.is_bit() {
+        let value = raw
+            .as_f64()
+            .ok_or_else(|| Error::invalid_parameter("Invalid coil value"))?
+            != 0.0;
+        connection
+            .write_single_coil(tag.slave_id, Indexed::new(tag.address, value), timeout)
+            .await
+            .map_err(|e| e.on_connection(id))?;
+    } else {
+        let registers = decoding::encode(&raw, tag.data_type, tag.byte_order)
+            .map_err(|e| e.on_connection(id))?;
+        connection
+            .write_multiple_registers(tag.slave_id, tag.address, registers, timeout)
+            .await
+            .map_err(|e| e.on_connection(id))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,5 @@
+pub mod controller;
+pub mod tag;
+pub mod tag_manager;
+
+pub use controller::*;
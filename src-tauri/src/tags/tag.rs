@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::{
+    errors::{Error, Result},
+    modbus::{
+        connection::RegisterType,
+        decoding::{self, ByteOrder, DataType, TypedValue},
+        planner::ReadPoint,
+    },
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub name: String,
+    pub slave_id: u8,
+    pub register_type: RegisterType,
+    pub address: u16,
+    pub data_type: DataType,
+    #[serde(default)]
+    pub byte_order: ByteOrder,
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    #[serde(default)]
+    pub offset: f64,
+    #[serde(default)]
+    pub unit: Option<String>,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct TagValue {
+    pub name: String,
+    pub value: TypedValue,
+    pub unit: Option<String>,
+}
+
+impl Tag {
+    pub fn is_bit(&self) -> bool {
+        matches!(
+            self.register_type,
+            RegisterType::Coil | RegisterType::DiscreteInput
+        )
+    }
+
+    pub fn is_writable(&self) -> bool {
+        matches!(
+            self.register_type,
+            RegisterType::Coil | RegisterType::HoldingRegister
+        )
+    }
+
+    pub fn register_count(&self) -> u16 {
+        if self.is_bit() {
+            1
+        } else {
+            self.data_type.register_count()
+        }
+    }
+
+    pub fn read_point(&self) -> ReadPoint {
+        ReadPoint {
+            slave_id: self.slave_id,
+            register_type: self.register_type,
+            address: self.address,
+            count: self.register_count(),
+        }
+    }
+
+    // Engineering value from the raw registers (or bits) of this tag
+    pub fn decode(&self, registers: &[u16]) -> Result<TypedValue> {
+        let raw = if self.is_bit() {
+            let bit = registers.first().ok_or_else(|| {
+                Error::invalid_parameter(format!("No value was read for tag {}", self.name))
+            })?;
+            TypedValue::Unsigned(*bit as u64)
+        } else {
+            decoding::decode(registers, self.data_type, self.byte_order)?
+        };
+
+        Ok(self.to_engineering(raw))
+    }
+
+    fn is_scaled(&self) -> bool {
+        self.scale != 1.0 || self.offset != 0.0
+    }
+
+    // Raw device value to engineering units
+    pub fn to_engineering(&self, raw: TypedValue) -> TypedValue {
+        match raw.as_f64() {
+            Some(value) if self.is_scaled() => TypedValue::Float(value * self.scale + self.offset),
+            _ => raw,
+        }
+    }
+
+    // Engineering units back to the raw device value
+    pub fn to_raw(&self, value: TypedValue) -> TypedValue {
+        let Some(value) = value.as_f64().filter(|_| self.is_scaled()) else {
+            return value;
+        };
+
+        let raw = (value - self.offset) / self.scale;
+        match self.data_type {
+            DataType::F32 | DataType::F64 => TypedValue::Float(raw),
+            _ => TypedValue::Float(raw.round()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(register_type: RegisterType, data_type: DataType) -> Tag {
+        Tag {
+            name: "level".to_string(),
+            slave_id: 1,
+            register_type,
+            address: 100,
+            data_type,
+            byte_order: ByteOrder::default(),
+            scale: 1.0,
+            offset: 0.0,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn decodes_bits_and_rejects_missing_values() {
+        let coil = tag(RegisterType::Coil, DataType::U16);
+        assert_eq!(coil.register_count(), 1);
+        assert_eq!(coil.decode(&[1]).unwrap(), TypedValue::Unsigned(1));
+        assert!(coil.decode(&[]).is_err());
+
+        let holding = tag(RegisterType::HoldingRegister, DataType::U32);
+        assert!(holding.decode(&[]).is_err());
+    }
+
+    #[test]
+    fn applies_scale_and_offset() {
+        let mut level = tag(RegisterType::HoldingRegister, DataType::U16);
+        assert_eq!(level.decode(&[250]).unwrap(), TypedValue::Unsigned(250));
+
+        level.scale = 0.5;
+        level.offset = -5.0;
+        assert_eq!(level.decode(&[250]).unwrap(), TypedValue::Float(120.0));
+        assert_eq!(
+            level.to_raw(TypedValue::Float(120.0)),
+            TypedValue::Float(250.0)
+        );
+        // Integer registers are written with the nearest raw value
+        assert_eq!(
+            level.to_raw(TypedValue::Float(120.2)),
+            TypedValue::Float(250.0)
+        );
+        assert_eq!(
+            level.to_raw(TypedValue::Text("on".to_string())),
+            TypedValue::Text("on".to_string())
+        );
+    }
+}
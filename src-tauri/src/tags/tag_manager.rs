@@ -0,0 +1,245 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use log::error;
+use tokio::{io::AsyncWriteExt, sync::RwLock};
+
+use crate::{
+    errors::{Result, TagError},
+    tags::tag::Tag,
+};
+
+// Keyed by device identity, see ModbusConnectionSettings::identity
+type TagGroups = BTreeMap<String, BTreeMap<String, Tag>>;
+
+// Tags grouped per device and persisted as JSON
+pub struct TagManager {
+    path: PathBuf,
+    groups: RwLock<TagGroups>,
+}
+
+impl TagManager {
+    // A file that does not parse is set aside rather than keeping the app
+    // from starting
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let groups = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+                let backup = path.with_extension("json.bak");
+                error!(
+                    "Failed to parse {}, moving it to {}: {}",
+                    path.display(),
+                    backup.display(),
+                    err
+                );
+                if let Err(err) = std::fs::rename(&path, &backup) {
+                    error!("Failed to back up {}: {}", path.display(), err);
+                }
+                TagGroups::new()
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => TagGroups::new(),
+            Err(err) => return Err(TagError::Io(err).into()),
+        };
+
+        Ok(Self {
+            path,
+            groups: RwLock::new(groups),
+        })
+    }
+
+    pub async fn list_tags(&self, device: &str) -> Vec<Tag> {
+        self.groups
+            .read()
+            .await
+            .get(device)
+            .map(|tags| tags.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_tag(&self, device: &str, name: &str) -> Result<Tag> {
+        self.groups
+            .read()
+            .await
+            .get(device)
+            .and_then(|tags| tags.get(name))
+            .cloned()
+            .ok_or_else(|| TagError::NotFound(name.to_string()).into())
+    }
+
+    pub async fn upsert_tag(&self, device: &str, tag: Tag) -> Result<()> {
+        let mut groups = self.groups.write().await;
+        groups
+            .entry(device.to_string())
+            .or_default()
+            .insert(tag.name.clone(), tag);
+        self.save(&groups).await
+    }
+
+    pub async fn delete_tag(&self, device: &str, name: &str) -> Result<()> {
+        let mut groups = self.groups.write().await;
+        let removed = groups.get_mut(device).and_then(|tags| tags.remove(name));
+        if removed.is_none() {
+            return Err(TagError::NotFound(name.to_string()).into());
+        }
+        self.save(&groups).await
+    }
+
+    // Written to a temporary file first, so a crash mid-write leaves the
+    // previous file intact
+    async fn save(&self, groups: &TagGroups) -> Result<()> {
+        let content = serde_json::to_string_pretty(groups).map_err(TagError::from)?;
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(TagError::Io)?;
+        }
+
+        let temp_path = self.path.with_extension("json.tmp");
+        let mut file = tokio::fs::File::create(&temp_path)
+            .await
+            .map_err(TagError::Io)?;
+        file.write_all(content.as_bytes())
+            .await
+            .map_err(TagError::Io)?;
+        file.sync_all().await.map_err(TagError::Io)?;
+        drop(file);
+        tokio::fs::rename(&temp_path, &self.path)
+            .await
+            .map_err(TagError::Io)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modbus::{
+        connection::{ModbusConnectionSettings, RegisterType},
+        decoding::{ByteOrder, DataType},
+    };
+    use std::time::Duration;
+
+    // Removed when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("modbusx-{}-{}", std::process::id(), name));
+            let _ = std::fs::remove_dir_all(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn tag(name: &str, address: u16) -> Tag {
+        Tag {
+            name: name.to_string(),
+            slave_id: 1,
+            register_type: RegisterType::HoldingRegister,
+            address,
+            data_type: DataType::F32,
+            byte_order: ByteOrder::CDAB,
+            scale: 0.1,
+            offset: -40.0,
+            unit: Some("°C".to_string()),
+        }
+    }
+
+    fn udp(host: &str, timeout: Duration) -> ModbusConnectionSettings {
+        ModbusConnectionSettings::UDP {
+            host: host.to_string(),
+            port: 502,
+            timeout,
+            retransmissions: 2,
+        }
+    }
+
+    #[tokio::test]
+    async fn tags_persist_across_loads() {
+        let dir = TempDir::new("tags-persist");
+        let path = dir.0.join("config").join("tags.json");
+
+        let manager = TagManager::load(path.clone()).unwrap();
+        assert!(manager.list_tags("udp://plc:502").await.is_empty());
+        manager
+            .upsert_tag("udp://plc:502", tag("temperature", 10))
+            .await
+            .unwrap();
+        manager
+            .upsert_tag("udp://plc:502", tag("pressure", 20))
+            .await
+            .unwrap();
+        manager
+            .delete_tag("udp://plc:502", "pressure")
+            .await
+            .unwrap();
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let reloaded = TagManager::load(path).unwrap();
+        let tags = reloaded.list_tags("udp://plc:502").await;
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].address, 10);
+        assert_eq!(tags[0].byte_order, ByteOrder::CDAB);
+        assert_eq!(tags[0].scale, 0.1);
+        assert_eq!(tags[0].offset, -40.0);
+        assert_eq!(tags[0].unit.as_deref(), Some("°C"));
+        assert!(reloaded.get_tag("udp://plc:502", "pressure").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn corrupt_file_is_set_aside() {
+        let dir = TempDir::new("tags-corrupt");
+        std::fs::create_dir_all(&dir.0).unwrap();
+        let path = dir.0.join("tags.json");
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let manager = TagManager::load(path.clone()).unwrap();
+        assert!(manager.list_tags("udp://plc:502").await.is_empty());
+        assert!(!path.exists());
+        assert_eq!(
+            std::fs::read_to_string(path.with_extension("json.bak")).unwrap(),
+            "{ not json"
+        );
+
+        // The next save starts a fresh file next to the backup
+        manager
+            .upsert_tag("udp://plc:502", tag("temperature", 10))
+            .await
+            .unwrap();
+        assert!(path.exists());
+        assert_eq!(
+            TagManager::load(path)
+                .unwrap()
+                .list_tags("udp://plc:502")
+                .await
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn tags_are_keyed_by_device_identity() {
+        let dir = TempDir::new("tags-identity");
+        let manager = TagManager::load(dir.0.join("tags.json")).unwrap();
+
+        // Reconnecting with other settings reaches the same device
+        let plc = udp("plc", Duration::from_millis(500)).identity();
+        assert_eq!(plc, udp("plc", Duration::from_secs(2)).identity());
+        let other = udp("other", Duration::from_millis(500)).identity();
+
+        manager
+            .upsert_tag(&plc, tag("temperature", 10))
+            .await
+            .unwrap();
+        assert_eq!(manager.list_tags(&plc).await.len(), 1);
+        assert!(manager.list_tags(&other).await.is_empty());
+        assert!(manager.get_tag(&other, "temperature").await.is_err());
+        assert!(manager.delete_tag(&other, "temperature").await.is_err());
+        assert!(manager.get_tag(&plc, "temperature").await.is_ok());
+    }
+}
@@ -0,0 +1,548 @@
+// @ts-nocheck
+
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async connectModbusTcp(id: number, host: string, port: number, timeout: number, retries: number, decodeLevel: string | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("connect_modbus_tcp", { id, host, port, timeout, retries, decodeLevel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async connectModbusRtu(id: number, path: string, baudRate: number, dataBits: number, parity: string, stopBits: number, timeout: number, retries: number, decodeLevel: string | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("connect_modbus_rtu", { id, path, baudRate, dataBits, parity, stopBits, timeout, retries, decodeLevel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async connectModbusRtuOverTcp(id: number, host: string, port: number, timeout: number, retries: number, frameGap: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("connect_modbus_rtu_over_tcp", { id, host, port, timeout, retries, frameGap }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async connectModbusAscii(id: number, path: string, baudRate: number, dataBits: number, parity: string, stopBits: number, timeout: number, retries: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("connect_modbus_ascii", { id, path, baudRate, dataBits, parity, stopBits, timeout, retries }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async connectModbusUdp(id: number, host: string, port: number, timeout: number, retries: number, retransmissions: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("connect_modbus_udp", { id, host, port, timeout, retries, retransmissions }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async connectModbusTls(id: number, host: string, port: number, timeout: number, retries: number, decodeLevel: string | null, serverName: string, caPath: string, certPath: string, keyPath: string, keyPassword: string | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("connect_modbus_tls", { id, host, port, timeout, retries, decodeLevel, serverName, caPath, certPath, keyPath, keyPassword }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async closeConnection(id: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("close_connection", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getConnectionStatus(id: number) : Promise<Result<ConnectionStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_connection_status", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startModbusSniffer(id: number, path: string, baudRate: number, dataBits: number, parity: string, stopBits: number, minGapMs: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_modbus_sniffer", { id, path, baudRate, dataBits, parity, stopBits, minGapMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopModbusSniffer(id: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_modbus_sniffer", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readCoils(id: number, startAddress: number, count: number, slaveId: number, timeout: number | null) : Promise<Result<IndexedValue[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_coils", { id, startAddress, count, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readDeviceIdentification(id: number, category: DeviceIdCategory, objectId: number | null, slaveId: number, timeout: number | null) : Promise<Result<DeviceIdentification, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_device_identification", { id, category, objectId, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async reportServerId(id: number, slaveId: number, timeout: number | null) : Promise<Result<ServerIdReport, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("report_server_id", { id, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readDiscreteInputs(id: number, startAddress: number, count: number, slaveId: number, timeout: number | null) : Promise<Result<IndexedValue[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_discrete_inputs", { id, startAddress, count, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readHoldingRegisters(id: number, startAddress: number, count: number, slaveId: number, timeout: number | null) : Promise<Result<IndexedValue[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_holding_registers", { id, startAddress, count, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readInputRegisters(id: number, startAddress: number, count: number, slaveId: number, timeout: number | null) : Promise<Result<IndexedValue[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_input_registers", { id, startAddress, count, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeMultipleCoils(id: number, startAddress: number, values: boolean[], slaveId: number, timeout: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_multiple_coils", { id, startAddress, values, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeMultipleRegisters(id: number, address: number, values: number[], slaveId: number, timeout: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_multiple_registers", { id, address, values, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeSingleCoil(id: number, address: number, value: boolean, slaveId: number, timeout: number | null) : Promise<Result<IndexedValue, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_single_coil", { id, address, value, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeSingleRegister(id: number, address: number, value: number, slaveId: number, timeout: number | null) : Promise<Result<IndexedValue, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_single_register", { id, address, value, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readWriteMultipleRegisters(id: number, readAddress: number, readCount: number, writeAddress: number, values: number[], slaveId: number, timeout: number | null) : Promise<Result<IndexedValue[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_write_multiple_registers", { id, readAddress, readCount, writeAddress, values, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async maskWriteRegister(id: number, address: number, andMask: number, orMask: number, slaveId: number, timeout: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mask_write_register", { id, address, andMask, orMask, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async diagnosticsLoopback(id: number, data: number[], slaveId: number, timeout: number | null) : Promise<Result<number[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diagnostics_loopback", { id, data, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readDiagnosticCounters(id: number, slaveId: number, timeout: number | null) : Promise<Result<DiagnosticCounters, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_diagnostic_counters", { id, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearDiagnosticCounters(id: number, slaveId: number, timeout: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_diagnostic_counters", { id, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readExceptionStatus(id: number, slaveId: number, timeout: number | null) : Promise<Result<number, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_exception_status", { id, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCommEventCounter(id: number, slaveId: number, timeout: number | null) : Promise<Result<CommEventCounter, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_comm_event_counter", { id, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCommEventLog(id: number, slaveId: number, timeout: number | null) : Promise<Result<CommEventLog, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_comm_event_log", { id, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readFifoQueue(id: number, pointerAddress: number, slaveId: number, timeout: number | null) : Promise<Result<FifoQueue, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_fifo_queue", { id, pointerAddress, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readFileRecord(id: number, requests: FileRecordRequest[], slaveId: number, timeout: number | null) : Promise<Result<FileRecord[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_file_record", { id, requests, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeFileRecord(id: number, records: FileRecord[], slaveId: number, timeout: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_file_record", { id, records, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sendRawPdu(id: number, functionCode: number, payload: number[], slaveId: number, timeout: number | null) : Promise<Result<RawResponse, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_raw_pdu", { id, functionCode, payload, slaveId, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getTraffic(id: number) : Promise<Result<TrafficRecord[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_traffic", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearTraffic(id: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_traffic", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportTraffic(id: number, path: string, format: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_traffic", { id, path, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setDecodeLevel(id: number, decodeLevel: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_decode_level", { id, decodeLevel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readDecodedRegisters(id: number, registerType: string, startAddress: number, count: number, slaveId: number, dataType: DataType, byteOrder: ByteOrder | null, timeout: number | null) : Promise<Result<DecodedValue[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_decoded_registers", { id, registerType, startAddress, count, slaveId, dataType, byteOrder, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeEncodedRegisters(id: number, address: number, value: TypedValue, slaveId: number, dataType: DataType, byteOrder: ByteOrder | null, timeout: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_encoded_registers", { id, address, value, slaveId, dataType, byteOrder, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listTags(id: number) : Promise<Result<Tag[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_tags", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveTag(id: number, tag: Tag) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_tag", { id, tag }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteTag(id: number, name: string) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_tag", { id, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readTag(id: number, name: string, timeout: number | null) : Promise<Result<TagValue, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_tag", { id, name, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readTags(id: number, names: string[], maxGap: number | null, timeout: number | null) : Promise<Result<TagValue[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_tags", { id, names, maxGap, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeTag(id: number, name: string, value: TypedValue, timeout: number | null) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_tag", { id, name, value, timeout }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startPollJob(id: number, jobId: number, registerType: string, startAddress: number, count: number, slaveId: number, intervalMs: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_poll_job", { id, jobId, registerType, startAddress, count, slaveId, intervalMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopPollJob(id: number, jobId: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_poll_job", { id, jobId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startModbusTcpServer(id: number, host: string, port: number, unitIds: number[], size: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_modbus_tcp_server", { id, host, port, unitIds, size }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startModbusRtuServer(id: number, path: string, baudRate: number, dataBits: number, parity: string, stopBits: number, unitIds: number[], size: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_modbus_rtu_server", { id, path, baudRate, dataBits, parity, stopBits, unitIds, size }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopModbusServer(id: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_modbus_server", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async readServerRegisters(id: number, unitId: number, registerType: string, startAddress: number, count: number) : Promise<Result<IndexedValue[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_server_registers", { id, unitId, registerType, startAddress, count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async writeServerRegisters(id: number, unitId: number, registerType: string, startAddress: number, values: number[]) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_server_registers", { id, unitId, registerType, startAddress, values }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async startModbusGateway(id: number, host: string, port: number, connectionId: number, routes: GatewayRoute[]) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_modbus_gateway", { id, host, port, connectionId, routes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopModbusGateway(id: number) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_modbus_gateway", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createWindow(url: string, title: string, visible: boolean | null, height: number | null, width: number | null, maximizable: boolean | null, resizable: boolean | null, trafficLightPosition: [number, number] | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_window", { url, title, visible, height, width, maximizable, resizable, trafficLightPosition }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getPorts() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_ports") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+export type ByteOrder = "ABCD" | "CDAB" | "BADC" | "DCBA"
+export type CommEventCounter = { busy: boolean; eventCount: number }
+export type CommEventLog = { busy: boolean; eventCount: number; messageCount: number; events: number[] }
+export type CommandError = { kind: string; message: string; exceptionCode: number | null; connectionId: number | null }
+export type ConnectionState = "connecting" | "connected" | "retrying" | "failed" | "closed"
+export type ConnectionStatus = { connectionId: number; state: ConnectionState; timestamp: number; message: string | null }
+export type DataType = "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "f32" | "f64" | "bcd16" | "bcd32" | { ascii: { length: number } }
+export type DecodedValue = { address: number; value: TypedValue }
+export type DeviceIdCategory = "basic" | "regular" | "extended" | "individual"
+export type DeviceIdentification = { conformityLevel: number; objects: DeviceObject[] }
+export type DeviceObject = { id: number; name: string | null; value: string; raw: number[] }
+export type DiagnosticCounters = { diagnosticRegister: number; busMessageCount: number; busCommunicationErrorCount: number; busExceptionErrorCount: number; serverMessageCount: number; serverNoResponseCount: number; serverNakCount: number; serverBusyCount: number; busCharacterOverrunCount: number }
+export type Direction = "request" | "response"
+export type FifoQueue = { count: number; values: number[] }
+export type FileRecord = { fileNumber: number; recordNumber: number; values: number[] }
+export type FileRecordRequest = { fileNumber: number; recordNumber: number; length: number }
+export type FrameRole = "request" | "response" | "invalid"
+export type GatewayRoute = { tcpUnitId: number; slaveId: number }
+export type IndexedValue = { address: number; value: number }
+export type PollEvent = { connectionId: number; jobId: number; timestamp: number; values: IndexedValue[] | null; error: string | null }
+export type RawResponse = { kind: "response"; functionCode: number; data: number[] } | { kind: "exception"; functionCode: number; exceptionCode: number; description: string }
+export type RegisterType = "coil" | "discrete" | "holding" | "input"
+export type ServerIdReport = { serverId: number; running: boolean; additionalData: number[] }
+export type SniffedFrame = { connectionId: number; sequence: number; timestamp: number; role: FrameRole; unitId: number; functionCode: number; exception: string | null; address: number | null; quantity: number | null; values: IndexedValue[]; raw: number[]; crcValid: boolean; latencyMs: number | null }
+export type Tag = { name: string; slaveId: number; registerType: RegisterType; address: number; dataType: DataType; byteOrder: ByteOrder; scale: number; offset: number; unit: string | null }
+export type TagValue = { name: string; value: TypedValue; unit: string | null }
+export type TrafficRecord = { connectionId: number; transactionId: number; timestamp: number; direction: Direction; unitId: number; functionCode: number; raw: number[]; reconstructed: boolean; crcValid: boolean | null; latencyMs: number | null; error: string | null }
+export type TypedValue = number | number | number | string
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
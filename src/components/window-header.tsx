@@ -1,4 +1,5 @@
-import { invoke } from "@tauri-apps/api/core";
+import { commands } from "@/bindings";
+import { unwrap } from "@/lib/errors";
 import { getAllWebviewWindows } from "@tauri-apps/api/webviewWindow";
 import { error } from "@tauri-apps/plugin-log";
 import { Settings } from "lucide-react";
@@ -7,16 +8,21 @@ import { Button } from "./ui/button";
 export function WindowHeader() {
   const handleOpenSettings = async () => {
     try {
-      await invoke("create_window", {
-        url: "settings",
-        title: "Settings",
-        visible: false,
-        height: 600,
-        width: 650,
-      });
+      unwrap(
+        await commands.createWindow(
+          "settings",
+          "Settings",
+          false,
+          600,
+          650,
+          null,
+          null,
+          null,
+        ),
+      );
     } catch (e: unknown) {
       if (
-        (e as string).includes("a webview with label `settings` already exists")
+        String(e).includes("a webview with label `settings` already exists")
       ) {
         const allWindows = await getAllWebviewWindows();
         const window = allWindows.find((w) => w.label === "settings");
@@ -25,7 +31,7 @@ export function WindowHeader() {
           window.setFocus();
         }
       }
-      error(e as string);
+      error(String(e));
     }
   };
 
@@ -1,14 +1,18 @@
+import type { ConnectionState, ConnectionStatus } from "@/bindings";
 import { create } from "zustand";
 
-type ConnectionState = "connecting" | "connected" | "disconnected";
+// ConnectionStatus is the payload of the "modbus-connection-status" event
+export type { ConnectionState, ConnectionStatus };
 
 interface UseConnection {
-  connection: ConnectionState;
-  setConnection: (newConnection: ConnectionState) => void;
+  statuses: Record<number, ConnectionStatus>;
+  setStatus: (status: ConnectionStatus) => void;
 }
 
 export const useConnection = create<UseConnection>((set) => ({
-  connection: "disconnected",
-  setConnection: (newConnection: ConnectionState) =>
-    set({ connection: newConnection }),
+  statuses: {},
+  setStatus: (status: ConnectionStatus) =>
+    set((state) => ({
+      statuses: { ...state.statuses, [status.connectionId]: status },
+    })),
 }));
@@ -1,9 +1,12 @@
+import { commands, PollEvent } from "@/bindings";
 import { useTabs } from "@/hooks/useTabs";
-import { ModbusAddress } from "@/types";
-import { invoke } from "@tauri-apps/api/core";
-import { useEffect } from "react";
+import { errorMessage, unwrap } from "@/lib/errors";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useRef } from "react";
 import { error } from "@tauri-apps/plugin-log";
 
+const POLL_JOB_ID = 1;
+
 export function useModbusPooling(tabId: number) {
   const setValues = useTabs((state) => state.setValues);
   const tab = useTabs((state) => state.tabs[tabId]);
@@ -16,42 +19,48 @@ export function useModbusPooling(tabId: number) {
   const slaveId = tab?.modbusConfig?.slaveId || 1;
   const registerType = tab?.modbusConfig?.registerType || "holding";
 
+  // Starts and stops run one after another, so the stop issued when the
+  // configuration changes cannot cancel the job started for the new one
+  const jobCalls = useRef<Promise<unknown>>(Promise.resolve());
+
   useEffect(() => {
     if (!isActive || !intervalMs || intervalMs <= 0) return;
-    const fetchData = async () => {
-      try {
-        let command;
-
-        if (registerType === "holding") {
-          command = "read_holding_registers";
-        } else if (registerType === "input") {
-          command = "read_input_registers";
-        } else if (registerType === "coil") {
-          command = "read_coils";
-        } else if (registerType === "discrete") {
-          command = "read_discrete_inputs";
-        } else {
-          throw new Error("Invalid register type");
-        }
-
-        const response: ModbusAddress[] = await invoke(command, {
-          id: tabId,
+
+    const unlisten = listen<PollEvent>("modbus-poll", (event) => {
+      const { connectionId, jobId, values } = event.payload;
+      if (connectionId !== tabId || jobId !== POLL_JOB_ID) return;
+
+      if (values) {
+        setValues(tabId, values);
+      } else {
+        setTabError(tabId, String(event.payload.error));
+        error("Modbus error in tab " + tabId + ": " + event.payload.error);
+      }
+    });
+
+    jobCalls.current = jobCalls.current.then(() =>
+      commands
+        .startPollJob(
+          tabId,
+          POLL_JOB_ID,
+          registerType,
           startAddress,
           count,
           slaveId,
-        });
-        setValues(tabId, response);
-      } catch (e) {
-        setTabError(tabId, String(e));
-        error("Modbus error in tab " + tabId + ": " + e);
-      }
-    };
-
-    fetchData();
+          intervalMs,
+        )
+        .then(unwrap)
+        .catch((e) => {
+          setTabError(tabId, errorMessage(e));
+          error("Modbus error in tab " + tabId + ": " + errorMessage(e));
+        }),
+    );
 
-    const interval = setInterval(fetchData, intervalMs);
     return () => {
-      clearInterval(interval);
+      unlisten.then((f) => f());
+      jobCalls.current = jobCalls.current.then(() =>
+        commands.stopPollJob(tabId, POLL_JOB_ID),
+      );
     };
   }, [tabId, isActive, intervalMs, startAddress, count, registerType, slaveId]);
 
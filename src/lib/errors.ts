@@ -0,0 +1,23 @@
+import type { CommandError, Result } from "@/bindings";
+
+export type { CommandError };
+
+export function isCommandError(e: unknown): e is CommandError {
+  return (
+    typeof e === "object" &&
+    e !== null &&
+    "kind" in e &&
+    "message" in e
+  );
+}
+
+export function errorMessage(e: unknown): string {
+  return isCommandError(e) ? e.message : String(e);
+}
+
+// Returns the data of a command result, throwing its error so callers can
+// handle failures with try/catch
+export function unwrap<T, E>(result: Result<T, E>): T {
+  if (result.status === "error") throw result.error;
+  return result.data;
+}
@@ -23,7 +23,22 @@ export const tcpFormSchema = z.object({
   reconnectInterval: z.coerce.number().int().min(100).max(60000),
 });
 
+export const tlsFormSchema = z.object({
+  type: z.literal("TLS"),
+  host: z.string().min(1, "Host is required"),
+  port: z.coerce.number().int().min(1).max(65535),
+  slaveId: z.coerce.number().int().min(0).max(255),
+  timeout: z.coerce.number().int().min(100).max(10000),
+  retries: z.coerce.number().int().min(0).max(10),
+  serverName: z.string().min(1, "Server name is required"),
+  caPath: z.string().min(1, "CA certificate is required"),
+  certPath: z.string().min(1, "Client certificate is required"),
+  keyPath: z.string().min(1, "Private key is required"),
+  keyPassword: z.string(),
+});
+
 export const modbusFormSchema = z.discriminatedUnion("type", [
   rtuFormSchema,
   tcpFormSchema,
+  tlsFormSchema,
 ]);
@@ -2,6 +2,7 @@ import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { ConnectionLayout } from "@/layouts/connection-layout";
 import { ModbusRTUForm } from "@/windows/add-connection/components/modbus-rtu-form";
 import { ModbusTCPForm } from "@/windows/add-connection/components/modbus-tcp-form";
+import { ModbusTLSForm } from "@/windows/add-connection/components/modbus-tls-form";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { error } from "@tauri-apps/plugin-log";
 import { ModbusFormValues } from "./types";
@@ -36,6 +37,12 @@ export function AddConnectionPage() {
           >
             RTU
           </TabsTrigger>
+          <TabsTrigger
+            value="tls"
+            className="data-[state=active]:bg-muted rounded-none border-b-0"
+          >
+            TLS
+          </TabsTrigger>
         </TabsList>
         <TabsContent value="tcp" className="max-h-screen overflow-y-scroll">
           <ModbusTCPForm onTCPSubmit={handleConnection} />
@@ -43,6 +50,9 @@ export function AddConnectionPage() {
         <TabsContent value="rtu" className="max-h-screen overflow-y-scroll">
           <ModbusRTUForm onRTUSubmit={handleConnection} />
         </TabsContent>
+        <TabsContent value="tls" className="max-h-screen overflow-y-scroll">
+          <ModbusTLSForm onTLSSubmit={handleConnection} />
+        </TabsContent>
       </Tabs>
     </ConnectionLayout>
   );
@@ -1,3 +1,4 @@
+import { commands } from "@/bindings";
 import { Button } from "@/components/ui/button";
 import {
   Form,
@@ -21,9 +22,9 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { Separator } from "@/components/ui/separator";
+import { unwrap } from "@/lib/errors";
 import { rtuFormSchema } from "@/schemas/modbus";
 import { zodResolver } from "@hookform/resolvers/zod";
-import { invoke } from "@tauri-apps/api/core";
 import { RotateCcw, Usb } from "lucide-react";
 import { useEffect, useState } from "react";
 import { useForm } from "react-hook-form";
@@ -58,7 +59,7 @@ export function ModbusRTUForm({
   useEffect(() => {
     const fetchPorts = async () => {
       try {
-        const response = await invoke<string[]>("get_ports");
+        const response = unwrap(await commands.getPorts());
         setPorts(response);
       } catch (error) {
         console.error("Failed to fetch ports:", error);
@@ -0,0 +1,192 @@
+import { Button } from "@/components/ui/button";
+import {
+  Form,
+  FormControl,
+  FormField,
+  FormItem,
+  FormLabel,
+} from "@/components/ui/form";
+import { Input } from "@/components/ui/input";
+import {
+  InputGroup,
+  InputGroupAddon,
+  InputGroupInput,
+  InputGroupText,
+} from "@/components/ui/input-group";
+import { Separator } from "@/components/ui/separator";
+import { modbusFormSchema } from "@/schemas/modbus";
+import { zodResolver } from "@hookform/resolvers/zod";
+import { Globe } from "lucide-react";
+import { useForm } from "react-hook-form";
+import { ModbusFormValues, TLSFormValues } from "../types";
+import { FormSection, FormSectionHeader } from "./form-section";
+
+const defaultTLSValues: Partial<TLSFormValues> = {
+  host: "",
+  port: 802,
+  slaveId: 1,
+  timeout: 1000,
+  retries: 3,
+  serverName: "",
+  caPath: "",
+  certPath: "",
+  keyPath: "",
+  keyPassword: "",
+};
+
+const certificateFields = [
+  { name: "caPath", label: "CA certificate", placeholder: "/path/to/ca.pem" },
+  {
+    name: "certPath",
+    label: "Client certificate",
+    placeholder: "/path/to/client.pem",
+  },
+  { name: "keyPath", label: "Private key", placeholder: "/path/to/client.key" },
+] as const;
+
+export function ModbusTLSForm({
+  onTLSSubmit,
+}: {
+  onTLSSubmit: (data: ModbusFormValues) => void;
+}) {
+  const tlsForm = useForm<ModbusFormValues>({
+    resolver: zodResolver(modbusFormSchema),
+    defaultValues: {
+      type: "TLS",
+      ...defaultTLSValues,
+    },
+  });
+  return (
+    <section className="flex h-full flex-col p-4">
+      <h1 className="mb-3 font-semibold">Modbus TLS Configuration</h1>
+      <Form {...tlsForm}>
+        <form
+          onSubmit={tlsForm.handleSubmit(onTLSSubmit)}
+          className="flex h-full flex-1 flex-col gap-y-6"
+        >
+          <FormSection className="space-y-3">
+            <FormSectionHeader>Modbus</FormSectionHeader>
+            <div className="flex gap-3">
+              <FormField
+                control={tlsForm.control}
+                name="host"
+                render={({ field }) => (
+                  <FormItem className="w-3/5">
+                    <FormLabel>Host</FormLabel>
+                    <InputGroup>
+                      <InputGroupAddon>
+                        <Globe />
+                      </InputGroupAddon>
+                      <FormControl>
+                        <InputGroupInput placeholder="192.168.0.1" {...field} />
+                      </FormControl>
+                    </InputGroup>
+                  </FormItem>
+                )}
+              />
+              <FormField
+                control={tlsForm.control}
+                name="port"
+                render={({ field }) => (
+                  <FormItem className="w-2/5">
+                    <FormLabel>Port</FormLabel>
+                    <FormControl>
+                      <Input placeholder="802" {...field} />
+                    </FormControl>
+                  </FormItem>
+                )}
+              />
+            </div>
+          </FormSection>
+
+          <Separator />
+
+          <FormSection className="space-y-3">
+            <FormSectionHeader>Certificates</FormSectionHeader>
+            <FormField
+              control={tlsForm.control}
+              name="serverName"
+              render={({ field }) => (
+                <FormItem>
+                  <FormLabel>Server name</FormLabel>
+                  <FormControl>
+                    <Input placeholder="plc.example.com" {...field} />
+                  </FormControl>
+                </FormItem>
+              )}
+            />
+            {certificateFields.map(({ name, label, placeholder }) => (
+              <FormField
+                key={name}
+                control={tlsForm.control}
+                name={name}
+                render={({ field }) => (
+                  <FormItem>
+                    <FormLabel>{label}</FormLabel>
+                    <FormControl>
+                      <Input placeholder={placeholder} {...field} />
+                    </FormControl>
+                  </FormItem>
+                )}
+              />
+            ))}
+            <FormField
+              control={tlsForm.control}
+              name="keyPassword"
+              render={({ field }) => (
+                <FormItem>
+                  <FormLabel>Key password</FormLabel>
+                  <FormControl>
+                    <Input type="password" placeholder="Optional" {...field} />
+                  </FormControl>
+                </FormItem>
+              )}
+            />
+          </FormSection>
+
+          <Separator />
+
+          <FormSection className="space-y-3">
+            <FormSectionHeader>General</FormSectionHeader>
+            <div className="flex gap-3">
+              <FormField
+                control={tlsForm.control}
+                name="timeout"
+                render={({ field }) => (
+                  <FormItem className="w-3/5">
+                    <FormLabel>Timeout </FormLabel>
+                    <InputGroup>
+                      <FormControl>
+                        <InputGroupInput placeholder="1000" {...field} />
+                      </FormControl>
+                      <InputGroupAddon align={"inline-end"}>
+                        <InputGroupText>ms</InputGroupText>
+                      </InputGroupAddon>
+                    </InputGroup>
+                  </FormItem>
+                )}
+              />
+
+              <FormField
+                control={tlsForm.control}
+                name="retries"
+                render={({ field }) => (
+                  <FormItem className="w-2/5">
+                    <FormLabel>Retries</FormLabel>
+                    <FormControl>
+                      <Input placeholder="3" {...field} />
+                    </FormControl>
+                  </FormItem>
+                )}
+              />
+            </div>
+          </FormSection>
+
+          <Button type="submit" className="mt-auto w-full">
+            Connect to TLS
+          </Button>
+        </form>
+      </Form>
+    </section>
+  );
+}
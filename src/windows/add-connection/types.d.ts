@@ -2,8 +2,10 @@ import {
   modbusFormSchema,
   rtuFormSchema,
   tcpFormSchema,
+  tlsFormSchema,
 } from "@/schemas/modbus";
 
 export type TCPFormValues = z.infer<typeof tcpFormSchema>;
 export type RTUFormValues = z.infer<typeof rtuFormSchema>;
+export type TLSFormValues = z.infer<typeof tlsFormSchema>;
 export type ModbusFormValues = z.infer<typeof modbusFormSchema>;
@@ -1,3 +1,4 @@
+import { commands } from "@/bindings";
 import { Button } from "@/components/ui/button";
 import {
   Dialog,
@@ -18,9 +19,9 @@ import {
 } from "@/components/ui/form";
 import { Input } from "@/components/ui/input";
 import { useTabs } from "@/hooks/useTabs";
+import { errorMessage, unwrap } from "@/lib/errors";
 import { ModbusAddress } from "@/types";
 import { zodResolver } from "@hookform/resolvers/zod";
-import { invoke } from "@tauri-apps/api/core";
 import { error } from "@tauri-apps/plugin-log";
 import { useEffect, useState } from "react";
 import { useForm } from "react-hook-form";
@@ -68,24 +69,30 @@ export function EditRegisterDialog({
   const onSubmit = async (values: z.infer<typeof formSchema>) => {
     try {
       if (tab.modbusConfig.registerType === "coil") {
-        await invoke("write_single_coil", {
-          id: activeTabId,
-          address: cell.address,
-          value: Boolean(values.value),
-          slaveId: tab.modbusConfig.slaveId,
-        });
+        unwrap(
+          await commands.writeSingleCoil(
+            activeTabId,
+            cell.address,
+            Boolean(values.value),
+            tab.modbusConfig.slaveId,
+            null,
+          ),
+        );
       } else if (tab.modbusConfig.registerType === "holding") {
-        await invoke("write_single_register", {
-          id: activeTabId,
-          address: cell.address,
-          value: values.value,
-          slaveId: tab.modbusConfig.slaveId,
-        });
+        unwrap(
+          await commands.writeSingleRegister(
+            activeTabId,
+            cell.address,
+            values.value,
+            tab.modbusConfig.slaveId,
+            null,
+          ),
+        );
       }
 
       setOpen(false);
     } catch (e) {
-      error("Error writing register: " + e);
+      error("Error writing register: " + errorMessage(e));
     }
   };
 
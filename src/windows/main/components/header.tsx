@@ -1,37 +1,51 @@
+import { commands, ConnectionState } from "@/bindings";
 import { CustomToolTip } from "@/components/custom-tooltip";
 import { Button, buttonVariants } from "@/components/ui/button";
+import { useConnection } from "@/hooks/useConnection";
 import { useTabs } from "@/hooks/useTabs";
+import { errorMessage, unwrap } from "@/lib/errors";
 import { cn } from "@/lib/utils";
 import { TabState } from "@/types";
-import { invoke } from "@tauri-apps/api/core";
 import { getAllWebviewWindows } from "@tauri-apps/api/webviewWindow";
 import { error } from "@tauri-apps/plugin-log";
 import { Plus } from "lucide-react";
 import { toast } from "sonner";
 
+const stateColors: Record<ConnectionState, string> = {
+  connecting: "bg-yellow-500",
+  connected: "bg-green-500",
+  retrying: "bg-orange-500",
+  failed: "bg-red-500",
+  closed: "bg-secondary",
+};
+
 export function Header() {
   const tabs = useTabs((state) => state.tabs);
   const closeTab = useTabs((state) => state.closeTab);
   const activeTabId = useTabs((state) => state.activeTabId);
   const setActiveTab = useTabs((state) => state.setActiveTab);
+  const statuses = useConnection((state) => state.statuses);
   const currentTab = useTabs((state) =>
     activeTabId != null ? state.tabs[activeTabId] : undefined,
   );
 
   const handleCreateWindow = async () => {
     try {
-      await invoke("create_window", {
-        url: "add-connection",
-        title: "New Connection",
-        visible: false,
-        width: 400,
-        height: 580,
-        maximizable: false,
-        resizable: false,
-      });
+      unwrap(
+        await commands.createWindow(
+          "add-connection",
+          "New Connection",
+          false,
+          580,
+          400,
+          false,
+          false,
+          null,
+        ),
+      );
     } catch (e: unknown) {
       if (
-        (e as string).includes(
+        String(e).includes(
           "a webview with label `add-connection` already exists",
         )
       ) {
@@ -42,20 +56,20 @@ export function Header() {
           window.setFocus();
         }
       }
-      error(e as string);
+      error(String(e));
     }
   };
 
   const handleCloseTab = async (tabId: number) => {
     try {
-      await invoke("close_connection", { id: tabId });
+      unwrap(await commands.closeConnection(tabId));
       const newActiveTabId =
         Object.values(tabs).find((t: TabState) => t.tabId !== tabId)?.tabId ??
         null;
       setActiveTab(newActiveTabId);
       closeTab(tabId);
     } catch (e: unknown) {
-      error(e as string);
+      error(errorMessage(e));
     }
   };
 
@@ -89,6 +103,17 @@ export function Header() {
             )}
             onClick={() => setActiveTab(tab.tabId)}
           >
+            {statuses[tab.tabId] && (
+              <div
+                title={
+                  statuses[tab.tabId].message ?? statuses[tab.tabId].state
+                }
+                className={cn(
+                  "size-2 rounded-full",
+                  stateColors[statuses[tab.tabId].state],
+                )}
+              />
+            )}
             Conexion {tab.tabId}
             <div
               role="button"
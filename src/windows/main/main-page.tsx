@@ -1,8 +1,10 @@
+import { commands, ConnectionStatus } from "@/bindings";
+import { useConnection } from "@/hooks/useConnection";
 import { useTabs } from "@/hooks/useTabs";
 import { MainLayout } from "@/layouts/main-layout";
+import { errorMessage, unwrap } from "@/lib/errors";
 import { DataView } from "@/windows/main/components/data-view";
 import { Header } from "@/windows/main/components/header";
-import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { error } from "@tauri-apps/plugin-log";
 import { useEffect } from "react";
@@ -13,34 +15,65 @@ function IndexPage() {
   const addTab = useTabs((state) => state.addTab);
   const setActiveTab = useTabs((state) => state.setActiveTab);
   const getTabsCount = useTabs((state) => state.getTabsCount);
+  const setStatus = useConnection((state) => state.setStatus);
 
   const handleConnection = async (id: number, payload: Payload) => {
     const { type } = payload.values;
 
     if (type === "TCP") {
-      const { port, host, retries } = payload.values;
-      await invoke("connect_modbus_tcp", {
-        id,
-        port,
-        host,
-        retries,
-      });
+      const { port, host, timeout, retries } = payload.values;
+      unwrap(
+        await commands.connectModbusTcp(id, host, port, timeout, retries, null),
+      );
 
       return host + ":" + port;
     } else if (type === "RTU") {
-      const { path, baudRate, dataBits, parity, stopBits, retries } =
+      const { port, baudRate, dataBits, parity, stopBits, timeout, retries } =
         payload.values;
-      await invoke("connect_modbus_rtu", {
-        id,
-        path,
-        baudRate,
-        dataBits,
-        parity,
-        stopBits,
+      unwrap(
+        await commands.connectModbusRtu(
+          id,
+          port,
+          baudRate,
+          dataBits,
+          parity,
+          Number(stopBits),
+          timeout,
+          retries,
+          null,
+        ),
+      );
+
+      return port;
+    } else if (type === "TLS") {
+      const {
+        host,
+        port,
+        timeout,
         retries,
-      });
+        serverName,
+        caPath,
+        certPath,
+        keyPath,
+        keyPassword,
+      } = payload.values;
+      unwrap(
+        await commands.connectModbusTls(
+          id,
+          host,
+          port,
+          timeout,
+          retries,
+          null,
+          serverName,
+          caPath,
+          certPath,
+          keyPath,
+          keyPassword || null,
+        ),
+      );
 
-      return path;
+      return host + ":" + port;
     }
   };
 
@@ -56,14 +89,20 @@ function IndexPage() {
           return "Connected!";
         },
         error: (e) => {
-          error(e);
+          error(errorMessage(e));
           return "Failed to connect";
         },
       });
     });
 
+    const unlistenStatus = listen<ConnectionStatus>(
+      "modbus-connection-status",
+      (event) => setStatus(event.payload),
+    );
+
     return () => {
       unlistenAddTab.then((f) => f());
+      unlistenStatus.then((f) => f());
     };
   }, []);
 